@@ -0,0 +1,67 @@
+use std::path::PathBuf;
+
+use interpolatable::Glyph;
+use skrifa::{setting::VariationSetting, FontRef, GlyphId};
+
+/// An on-disk cache of built glyphs, so that repeated runs over the same
+/// font don't need to recompute statistics and isomorphisms.
+///
+/// Entries are keyed by a hash of the font's SHA1, the rounding grid, the
+/// glyph ID and the location. The version of this program is hashed in too,
+/// so that glyphs cached by another version, which may serialize them
+/// differently or compute different statistics, aren't reused.
+pub(crate) struct GlyphCache {
+    dir: PathBuf,
+    font_sha: String,
+    grid: Option<f64>,
+}
+
+impl GlyphCache {
+    pub fn new(dir: PathBuf, fontdata: &[u8], grid: Option<f64>) -> std::io::Result<Self> {
+        std::fs::create_dir_all(&dir)?;
+        let mut hasher = sha1_smol::Sha1::new();
+        hasher.update(fontdata);
+        Ok(GlyphCache {
+            dir,
+            font_sha: hasher.digest().to_string(),
+            grid,
+        })
+    }
+
+    fn path_for(&self, glyph_id: GlyphId, location: &[VariationSetting]) -> PathBuf {
+        let mut hasher = sha1_smol::Sha1::new();
+        hasher.update(env!("CARGO_PKG_VERSION").as_bytes());
+        hasher.update(self.font_sha.as_bytes());
+        if let Some(grid) = self.grid {
+            hasher.update(&grid.to_be_bytes());
+        }
+        hasher.update(&glyph_id.to_u32().to_be_bytes());
+        for setting in location {
+            hasher.update(&setting.selector.to_be_bytes());
+            hasher.update(&setting.value.to_be_bytes());
+        }
+        self.dir.join(format!("{}.json", hasher.digest()))
+    }
+
+    /// Load a glyph from the cache, or build it from the font and store it
+    pub fn get_or_build(
+        &self,
+        font: &FontRef,
+        glyph_id: GlyphId,
+        location: &[VariationSetting],
+    ) -> Option<Glyph> {
+        let path = self.path_for(glyph_id, location);
+        if let Some(glyph) = std::fs::read(&path)
+            .ok()
+            .and_then(|data| serde_json::from_slice(&data).ok())
+        {
+            return Some(glyph);
+        }
+        let glyph = Glyph::new_from_font_on_grid(font, glyph_id, location, self.grid)?;
+        if let Ok(data) = serde_json::to_vec(&glyph) {
+            // A failure to write the cache shouldn't stop the run
+            let _ = std::fs::write(&path, data);
+        }
+        Some(glyph)
+    }
+}
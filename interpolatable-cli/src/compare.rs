@@ -0,0 +1,251 @@
+use std::{
+    collections::{HashMap, HashSet},
+    path::Path,
+};
+
+use indexmap::IndexMap;
+use interpolatable::{
+    utils::{glyph_name_for_id, glyph_variations},
+    BezGlyph, Problem,
+};
+use read_fonts::TableProvider;
+use serde::Serialize;
+use skrifa::{FontRef, GlyphId, MetadataProvider};
+
+/// The problems which differ between two versions of a font
+#[derive(Serialize, Default)]
+pub(crate) struct ReportChanges<'a> {
+    /// Problems in the new version which weren't in the old one
+    pub new: IndexMap<&'a str, Vec<&'a Problem>>,
+    /// Problems in the old version which are no longer in the new one
+    pub fixed: IndexMap<&'a str, Vec<&'a Problem>>,
+}
+
+impl ReportChanges<'_> {
+    pub fn print(&self) {
+        if self.new.is_empty() && self.fixed.is_empty() {
+            println!("No changes in interpolation problems");
+        }
+        for (glyphname, problems) in self.new.iter() {
+            println!("New problems with glyph {}:", glyphname);
+            for problem in problems {
+                println!("  {:#?}", problem);
+            }
+        }
+        for (glyphname, problems) in self.fixed.iter() {
+            println!("Fixed problems with glyph {}:", glyphname);
+            for problem in problems {
+                println!("  {:#?}", problem);
+            }
+        }
+    }
+}
+
+/// Identify a problem independently of its measurements
+///
+/// Glyphs are matched between versions by name, and masters by their
+/// location, so that glyph ID changes and reordered masters don't show up
+/// as spurious differences. Values such as tolerances are expected to shift
+/// a little with any outline edit, so they are not compared.
+fn problem_key(problem: &Problem) -> (String, &str, &str, Option<usize>, Option<usize>) {
+    (
+        problem.problem_type(),
+        &problem.master_1_name,
+        &problem.master_2_name,
+        problem.contour,
+        problem.node,
+    )
+}
+
+/// Problems in `these` which have no counterpart in `those`
+fn unmatched<'a>(these: &'a [Problem], those: &[Problem]) -> Vec<&'a Problem> {
+    these
+        .iter()
+        .filter(|p| !those.iter().any(|q| problem_key(p) == problem_key(q)))
+        .collect()
+}
+
+/// Compare the reports for two versions of a font
+pub(crate) fn compare_reports<'a>(
+    old: &'a IndexMap<String, Vec<Problem>>,
+    new: &'a IndexMap<String, Vec<Problem>>,
+) -> ReportChanges<'a> {
+    let mut changes = ReportChanges::default();
+    for (glyphname, new_problems) in new.iter() {
+        let old_problems = old.get(glyphname).map(|v| v.as_slice()).unwrap_or(&[]);
+        let added = unmatched(new_problems, old_problems);
+        if !added.is_empty() {
+            changes.new.insert(glyphname, added);
+        }
+    }
+    for (glyphname, old_problems) in old.iter() {
+        let new_problems = new.get(glyphname).map(|v| v.as_slice()).unwrap_or(&[]);
+        let removed = unmatched(old_problems, new_problems);
+        if !removed.is_empty() {
+            changes.fixed.insert(glyphname, removed);
+        }
+    }
+    changes
+}
+
+/// Read the names of the glyphs with problems from a previous JSON report
+///
+/// Both the plain report and the form with a `problems` key (as written
+/// with `--status` or `--report-margins`) are accepted.
+pub(crate) fn glyphs_in_report(path: &Path) -> Result<HashSet<String>, String> {
+    let contents = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+    let report: serde_json::Value = serde_json::from_str(&contents).map_err(|e| e.to_string())?;
+    let problems = match report.get("problems") {
+        Some(problems @ serde_json::Value::Object(_)) => problems,
+        _ => &report,
+    };
+    problems
+        .as_object()
+        .map(|glyphs| glyphs.keys().cloned().collect())
+        .ok_or_else(|| "Report is not a JSON object".to_string())
+}
+
+/// A hash of a glyph's outline at the default location and at each of its masters
+fn outline_hash(font: &FontRef, glyph_id: GlyphId) -> Option<String> {
+    let outline = font.outline_glyphs().get(glyph_id)?;
+    let mut hasher = sha1_smol::Sha1::new();
+    let masters =
+        std::iter::once(vec![]).chain(glyph_variations(font, glyph_id).unwrap_or_default());
+    for location in masters {
+        for setting in location.iter() {
+            hasher.update(&setting.selector.to_be_bytes());
+            hasher.update(&setting.value.to_be_bytes());
+        }
+        let loc = font.axes().location(&location);
+        let settings =
+            skrifa::outline::DrawSettings::unhinted(skrifa::prelude::Size::unscaled(), &loc);
+        let mut bezglyph = BezGlyph::default();
+        outline.draw(settings, &mut bezglyph).ok()?;
+        for path in bezglyph.iter() {
+            hasher.update(path.to_svg().as_bytes());
+        }
+    }
+    Some(hasher.digest().to_string())
+}
+
+/// A hash of the outlines of every glyph in a font, by glyph name
+fn outline_hashes(font: &FontRef) -> HashMap<String, Option<String>> {
+    let num_glyphs = font.maxp().map(|maxp| maxp.num_glyphs()).unwrap_or(0);
+    (0..num_glyphs)
+        .filter_map(|gid| {
+            let name = glyph_name_for_id(font, gid.into()).ok()?;
+            Some((name, outline_hash(font, gid.into())))
+        })
+        .collect()
+}
+
+/// The names of the glyphs whose outlines differ between two versions of a font
+///
+/// A glyph is unchanged if it has the same outline at every master in both
+/// versions (glyphs are matched by name, so renumbering doesn't count as a
+/// change). Glyphs which are only in one of the versions, or whose outlines
+/// can't be drawn, count as changed.
+pub(crate) fn changed_glyphs(old: &FontRef, new: &FontRef) -> HashSet<String> {
+    let old_hashes = outline_hashes(old);
+    let new_hashes = outline_hashes(new);
+    let changed =
+        |name: &String, hash: &Option<String>, other: &HashMap<String, Option<String>>| {
+            hash.is_none() || other.get(name) != Some(hash)
+        };
+    old_hashes
+        .iter()
+        .filter(|(name, hash)| changed(name, hash, &new_hashes))
+        .chain(
+            new_hashes
+                .iter()
+                .filter(|(name, hash)| changed(name, hash, &old_hashes)),
+        )
+        .map(|(name, _)| name.clone())
+        .collect()
+}
+
+/// The glyph names in a font, in glyph order
+fn glyph_order(font: &FontRef) -> Vec<String> {
+    let num_glyphs = font.maxp().map(|maxp| maxp.num_glyphs()).unwrap_or(0);
+    (0..num_glyphs)
+        .map(|gid| glyph_name_for_id(font, gid.into()).unwrap_or_else(|_| format!("gid{}", gid)))
+        .collect()
+}
+
+/// How the glyph sets of several fonts differ
+#[derive(Default)]
+pub(crate) struct GlyphSetReport {
+    /// Glyphs which are missing from some of the fonts, with the fonts they are missing from
+    pub missing: IndexMap<String, Vec<String>>,
+    /// Fonts whose shared glyphs are in a different order from the first font
+    pub reordered: Vec<String>,
+}
+
+impl GlyphSetReport {
+    /// Compare the glyph sets of labelled fonts
+    ///
+    /// Glyphs are compared by name. The glyph order only matters to tools
+    /// which match glyphs by ID, but is reported as a sign that the fonts
+    /// were built from different sources.
+    pub fn new(fonts: &[(String, &FontRef)]) -> Self {
+        let orders: Vec<(&String, Vec<String>)> = fonts
+            .iter()
+            .map(|(label, font)| (label, glyph_order(font)))
+            .collect();
+        let sets: Vec<HashSet<&String>> = orders
+            .iter()
+            .map(|(_, order)| order.iter().collect())
+            .collect();
+        let mut report = GlyphSetReport::default();
+        for (_, order) in orders.iter() {
+            for name in order {
+                if report.missing.contains_key(name) {
+                    continue;
+                }
+                let missing_from: Vec<String> = orders
+                    .iter()
+                    .zip(sets.iter())
+                    .filter(|(_, set)| !set.contains(name))
+                    .map(|((label, _), _)| label.to_string())
+                    .collect();
+                if !missing_from.is_empty() {
+                    report.missing.insert(name.clone(), missing_from);
+                }
+            }
+        }
+        let shared = |order: &[String]| -> Vec<String> {
+            order
+                .iter()
+                .filter(|name| !report.missing.contains_key(*name))
+                .cloned()
+                .collect()
+        };
+        if let Some((_, first)) = orders.first() {
+            let first = shared(first);
+            report.reordered = orders
+                .iter()
+                .skip(1)
+                .filter(|(_, order)| shared(order) != first)
+                .map(|(label, _)| label.to_string())
+                .collect();
+        }
+        report
+    }
+
+    /// Print the differences as warnings, so they don't get mixed up with the report
+    pub fn warn(&self) {
+        for (glyphname, fonts) in self.missing.iter() {
+            eprintln!(
+                "Warning: glyph {} is missing from {}",
+                glyphname,
+                fonts.join(", ")
+            );
+        }
+        for label in self.reordered.iter() {
+            eprintln!(
+                "Warning: {} has its glyphs in a different order (glyphs are matched by name)",
+                label
+            );
+        }
+    }
+}
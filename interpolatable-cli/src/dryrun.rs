@@ -0,0 +1,121 @@
+use std::time::{Duration, Instant};
+
+use interpolatable::utils::{glyph_name_for_id, glyph_variations, mapped_glyphs};
+use serde::Serialize;
+use skrifa::{setting::VariationSetting, FontRef};
+
+use crate::{glyph_range, locations, select_glyph, test_pair, units_per_em, Args};
+
+/// How many pairs of masters to test when estimating the time a check will take
+const SAMPLE_PAIRS: usize = 20;
+
+/// What a check would test, and roughly how long it would take
+#[derive(Serialize)]
+pub(crate) struct DryRun {
+    /// The number of glyphs which would be checked
+    pub glyphs: usize,
+    /// The number of distinct master locations among those glyphs
+    pub masters: usize,
+    /// The number of pairs of masters which would be compared
+    pub comparisons: usize,
+    /// The estimated time for the whole check, in seconds
+    pub estimated_seconds: f64,
+}
+
+impl DryRun {
+    /// Work out what checking `font` with `args` would involve
+    ///
+    /// The glyphs are selected as they would be for the real check. The time
+    /// is estimated by testing the first few pairs of masters, so it is only a
+    /// rough guide (and ignores the time spent drawing any reports).
+    pub(crate) fn new(font: &FontRef, args: &Args) -> Self {
+        let upem = units_per_em(font);
+        let mapped = mapped_glyphs(font);
+        let extra_locations = args
+            .locations
+            .as_ref()
+            .map(|path| locations::load_locations(path).expect("Can't read locations file"))
+            .unwrap_or_default();
+        let only = args.only_from.as_ref().map(|path| {
+            crate::compare::glyphs_in_report(path).expect("Can't read previous report")
+        });
+        let only: Vec<_> = only.iter().collect();
+        let tolerance_config = args.tolerance_config();
+
+        let mut glyphs = 0;
+        let mut comparisons = 0;
+        let mut locations: Vec<Vec<VariationSetting>> = vec![vec![]];
+        let mut sampled = 0;
+        let mut sample_time = Duration::ZERO;
+        for gid in glyph_range(font, args) {
+            if select_glyph(font, gid, args, &mapped, &only).is_err() {
+                continue;
+            }
+            let Ok(mut variations) = glyph_variations(font, gid.into()) else {
+                continue;
+            };
+            if variations.is_empty() {
+                continue;
+            }
+            for loc in extra_locations.iter() {
+                if !variations.contains(loc) {
+                    variations.push(loc.clone());
+                }
+            }
+            for loc in variations.iter() {
+                if !locations.contains(loc) {
+                    locations.push(loc.clone());
+                }
+            }
+            glyphs += 1;
+            comparisons += variations.len();
+
+            if sampled < SAMPLE_PAIRS {
+                let start = Instant::now();
+                let masters = std::iter::once(vec![])
+                    .chain(variations)
+                    .take(SAMPLE_PAIRS - sampled + 1)
+                    .filter_map(|loc| {
+                        interpolatable::Glyph::new_from_font_on_grid(
+                            font,
+                            gid.into(),
+                            &loc,
+                            args.round_to,
+                        )
+                    })
+                    .collect::<Vec<_>>();
+                let tolerances = tolerance_config
+                    .as_ref()
+                    .map(|config| {
+                        config.for_glyph(
+                            &glyph_name_for_id(font, gid.into()).expect("Can't get name"),
+                        )
+                    })
+                    .unwrap_or_default();
+                for pair in masters.windows(2) {
+                    test_pair(&pair[0], &pair[1], args, tolerances, upem);
+                    sampled += 1;
+                }
+                sample_time += start.elapsed();
+            }
+        }
+        let estimated_seconds = if sampled > 0 {
+            sample_time.as_secs_f64() / sampled as f64 * comparisons as f64
+        } else {
+            0.0
+        };
+        DryRun {
+            glyphs,
+            masters: locations.len(),
+            comparisons,
+            estimated_seconds,
+        }
+    }
+
+    pub(crate) fn print(&self) {
+        println!(
+            "Would test {} glyphs across {} masters: {} comparisons, estimated {:.0} seconds",
+            self.glyphs, self.masters, self.comparisons, self.estimated_seconds
+        );
+    }
+}
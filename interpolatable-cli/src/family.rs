@@ -0,0 +1,84 @@
+use std::path::Path;
+
+use indexmap::IndexMap;
+use interpolatable::Problem;
+use serde::Serialize;
+use skrifa::{string::StringId, FontRef, MetadataProvider};
+
+use crate::markdown::markdown_report;
+
+/// The problems found in each font of a family
+#[derive(Serialize, Default)]
+pub(crate) struct FamilyReport {
+    /// Problems per glyph, per font
+    pub fonts: IndexMap<String, IndexMap<String, Vec<Problem>>>,
+    /// How many problems of each type were found, per font
+    pub summary: IndexMap<String, IndexMap<String, usize>>,
+}
+
+impl FamilyReport {
+    /// Add the report for one font
+    ///
+    /// Fonts are labelled by family name; if two fonts have the same family
+    /// name (such as the upright and italic of a family), the file name is
+    /// added to tell them apart.
+    pub fn add(&mut self, font: &FontRef, path: &Path, problems: IndexMap<String, Vec<Problem>>) {
+        let filename = path
+            .file_name()
+            .map(|f| f.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let mut label = family_name(font).unwrap_or_else(|| filename.clone());
+        if self.fonts.contains_key(&label) {
+            label = format!("{} ({})", label, filename);
+        }
+        let mut summary: IndexMap<String, usize> = IndexMap::new();
+        for problem in problems.values().flatten() {
+            *summary.entry(problem.problem_type()).or_default() += 1;
+        }
+        self.summary.insert(label.clone(), summary);
+        self.fonts.insert(label, problems);
+    }
+
+    pub fn print(&self) {
+        for (label, problems) in self.fonts.iter() {
+            for (glyphname, problems) in problems.iter() {
+                println!("Problems with glyph {} in {}:", glyphname, label);
+                for problem in problems {
+                    println!("  {:#?}", problem);
+                }
+            }
+        }
+        println!("Summary:");
+        for (label, summary) in self.summary.iter() {
+            if summary.is_empty() {
+                println!("  {}: no problems", label);
+                continue;
+            }
+            let counts = summary
+                .iter()
+                .map(|(problem_type, count)| format!("{} {}", count, problem_type))
+                .collect::<Vec<_>>()
+                .join(", ");
+            println!("  {}: {}", label, counts);
+        }
+    }
+
+    pub fn markdown(&self) -> String {
+        self.fonts
+            .iter()
+            .map(|(label, problems)| format!("## {}\n\n{}", label, markdown_report(problems)))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// The font's family name, preferring the typographic family name
+fn family_name(font: &FontRef) -> Option<String> {
+    font.localized_strings(StringId::TYPOGRAPHIC_FAMILY_NAME)
+        .english_or_first()
+        .or_else(|| {
+            font.localized_strings(StringId::FAMILY_NAME)
+                .english_or_first()
+        })
+        .map(|s| s.to_string())
+}
@@ -0,0 +1,99 @@
+use skrifa::{setting::VariationSetting, FontRef, MetadataProvider, Tag};
+
+/// Locations closer together than this (in normalized axis units) are the same
+const SAME_LOCATION: f32 = 1e-3;
+
+/// An axis, with the information needed to measure distances along it
+struct Axis {
+    tag: Tag,
+    default: f32,
+    range: f32,
+}
+
+/// The font's named instances, used to describe master locations in the
+/// terms designers use ("Bold", "Condensed") rather than as axis values
+pub(crate) struct NamedInstances {
+    axes: Vec<Axis>,
+    /// The name of each instance, and its user coordinates in axis order
+    instances: Vec<(String, Vec<f32>)>,
+}
+
+impl NamedInstances {
+    pub(crate) fn new(font: &FontRef) -> Self {
+        let axes = font
+            .axes()
+            .iter()
+            .map(|axis| Axis {
+                tag: axis.tag(),
+                default: axis.default_value(),
+                range: axis.max_value() - axis.min_value(),
+            })
+            .collect();
+        let instances = font
+            .named_instances()
+            .iter()
+            .filter_map(|instance| {
+                let name = font
+                    .localized_strings(instance.subfamily_name_id())
+                    .english_or_first()?
+                    .to_string();
+                Some((name, instance.user_coords().collect()))
+            })
+            .collect();
+        NamedInstances { axes, instances }
+    }
+
+    /// The names of the named instances nearest to a location
+    ///
+    /// Several names are returned if more than one instance is equally near;
+    /// in particular, a location halfway between two instances gets both.
+    pub(crate) fn nearest(&self, location: &[VariationSetting]) -> Vec<String> {
+        let coords: Vec<f32> = self
+            .axes
+            .iter()
+            .map(|axis| {
+                location
+                    .iter()
+                    .find(|setting| setting.selector == axis.tag)
+                    .map_or(axis.default, |setting| setting.value)
+            })
+            .collect();
+        let distances: Vec<f32> = self
+            .instances
+            .iter()
+            .map(|(_, instance_coords)| {
+                self.axes
+                    .iter()
+                    .zip(coords.iter().zip(instance_coords.iter()))
+                    .map(|(axis, (a, b))| {
+                        if axis.range > 0.0 {
+                            ((a - b) / axis.range).powi(2)
+                        } else {
+                            0.0
+                        }
+                    })
+                    .sum::<f32>()
+                    .sqrt()
+            })
+            .collect();
+        let Some(nearest) = distances.iter().copied().reduce(f32::min) else {
+            return vec![];
+        };
+        self.instances
+            .iter()
+            .zip(distances.iter())
+            .filter(|(_, &distance)| distance - nearest < SAME_LOCATION)
+            .map(|((name, _), _)| name.clone())
+            .collect()
+    }
+
+    /// Add the names of the nearest named instances to a master name
+    pub(crate) fn describe(&self, master_name: String, location: &[VariationSetting]) -> String {
+        let names = self.nearest(location);
+        if names.is_empty() {
+            master_name
+        } else {
+            format!("{} ({})", master_name, names.join(", "))
+        }
+    }
+}
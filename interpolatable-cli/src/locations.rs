@@ -0,0 +1,59 @@
+use std::path::Path;
+
+use indexmap::IndexMap;
+use skrifa::setting::VariationSetting;
+
+/// Read a list of user-specified locations to test
+///
+/// The file may either be JSON, containing a list of objects mapping axis
+/// tags to values (`[{"wght": 450, "wdth": 87.5}]`), or CSV, with a header
+/// row of axis tags and one location per row. Files ending in `.csv` are
+/// treated as CSV and anything else as JSON.
+pub(crate) fn load_locations(path: &Path) -> Result<Vec<Vec<VariationSetting>>, String> {
+    let contents = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+    let locations: Vec<IndexMap<String, f32>> = if path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("csv"))
+    {
+        parse_csv(&contents)?
+    } else {
+        serde_json::from_str(&contents).map_err(|e| e.to_string())?
+    };
+    Ok(locations
+        .into_iter()
+        .map(|location| {
+            location
+                .iter()
+                .map(|(tag, value)| (tag.as_str(), *value).into())
+                .collect()
+        })
+        .collect())
+}
+
+fn parse_csv(contents: &str) -> Result<Vec<IndexMap<String, f32>>, String> {
+    let mut lines = contents.lines().filter(|line| !line.trim().is_empty());
+    let header: Vec<&str> = lines
+        .next()
+        .ok_or("Empty locations file")?
+        .split(',')
+        .map(|tag| tag.trim())
+        .collect();
+    lines
+        .map(|line| {
+            let values = line.split(',').map(|v| v.trim()).collect::<Vec<_>>();
+            if values.len() != header.len() {
+                return Err(format!("Wrong number of values in line '{}'", line));
+            }
+            header
+                .iter()
+                .zip(values)
+                .map(|(tag, value)| {
+                    value
+                        .parse::<f32>()
+                        .map(|v| (tag.to_string(), v))
+                        .map_err(|e| format!("Bad value '{}' for {}: {}", value, tag, e))
+                })
+                .collect()
+        })
+        .collect()
+}
@@ -1,56 +1,726 @@
+mod cache;
 mod cairopen;
+mod compare;
+mod dryrun;
+mod family;
+mod instances;
+mod locations;
+mod markdown;
+mod patterns;
 mod plot;
+mod stat;
+mod svg;
+mod tolerances;
 
-use std::{collections::HashMap, path::PathBuf};
+use std::{
+    collections::{HashMap, HashSet},
+    ops::Range,
+    path::PathBuf,
+};
 
-use clap::Parser;
+use cache::GlyphCache;
+use clap::{Parser, Subcommand};
 use indexmap::IndexMap;
 use indicatif::ProgressIterator;
 use interpolatable::{
-    run_tests,
-    utils::{glyph_name_for_id, glyph_variations},
-    Problem,
+    add_kink_trajectories, calibrate, hausdorff_distance, limit_problems, min_passing_tolerance,
+    retain_confident_start_points, retain_persistent_kinks, run_tests_with, test_centroid_drift,
+    test_extrapolation, test_frechet_distance, test_identical_master, test_metrics,
+    test_named_instances, test_point_collisions, test_segment_curvature, test_shared_shapes,
+    test_tangent_continuity, tolerance_margin,
+    utils::{glyph_name_for_id, glyph_variations, is_exported, mapped_glyphs},
+    GlyphPairReport, GlyphStatus, Problem, ProblemDetails, RunTestsOptions, Statistics, Tolerances,
 };
+use itertools::Itertools;
 use plot::InterpolatablePlot;
 use read_fonts::TableProvider;
-use skrifa::{setting::VariationSetting, FontRef, GlyphId};
+use serde::Serialize;
+use skrifa::{setting::VariationSetting, FontRef, GlyphId, MetadataProvider};
+use tolerances::{GlyphTolerances, ToleranceConfig};
+
+/// How many points of each kink's trajectory to report with --kink-trajectories
+const KINK_TRAJECTORY_SAMPLES: usize = 11;
+
+#[derive(clap::ArgEnum, Clone, Copy, Debug, PartialEq)]
+enum OutputFormat {
+    Text,
+    Json,
+    Markdown,
+}
 
 #[derive(Parser, Debug)]
 #[clap(author, version, about, long_about = None)]
-pub struct Args {
-    /// Output JSON
-    #[clap(short, long)]
-    json: bool,
+struct Cli {
+    #[clap(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Check a variable font for interpolation problems
+    Check(CheckArgs),
+    /// Report the problems which are new, or have been fixed, since an
+    /// earlier version of a font
+    Diff(DiffArgs),
+    /// Check that two static fonts would interpolate, as if they were two
+    /// masters of one font
+    Compare(CompareArgs),
+    /// Check that a variable font's named instances match static fonts of
+    /// the same instances
+    Statics(StaticsArgs),
+    /// Suggest tolerances from a variable font which is known to interpolate well
+    Calibrate(CalibrateArgs),
+}
+
+#[derive(clap::Args, Debug)]
+struct CheckArgs {
+    #[clap(flatten)]
+    common: Args,
+
+    /// In JSON output, add a summary of how many problems of each type were found
+    #[clap(long)]
+    with_summary: bool,
 
     /// Output to PDF files
     #[clap(short, long)]
     pdf: Option<String>,
 
+    /// Write an SVG file for each glyph with problems into this directory
+    #[clap(long)]
+    svg_dir: Option<PathBuf>,
+
+    /// In the PDF, overlay the default master's outline on each other master
+    #[clap(long)]
+    reference_overlay: bool,
+
+    /// In the PDF, show the glyph at every master alongside each problem,
+    /// with the two masters involved framed
+    #[clap(long)]
+    thumbnails: bool,
+
+    /// Preferred language (e.g. `ja` or `ar-EG`) for names shown in the PDF
+    #[clap(long)]
+    lang: Option<String>,
+
+    /// Also check that each named instance matches the interpolation of the
+    /// masters on either side of it, reporting problems by instance
+    #[clap(long)]
+    named_instances: bool,
+
+    /// Group the problems by the style axes declared in the font's STAT
+    /// table (e.g. weight problems, then width problems)
+    #[clap(long)]
+    by_axis: bool,
+
+    /// Also check these fonts from the same family, and report on all of
+    /// them together with a count of each type of problem per font
+    #[clap(long, multiple_values = true)]
+    family: Vec<PathBuf>,
+
+    /// Report how many glyphs and pairs of masters would be tested, and
+    /// roughly how long it would take, without running the check
+    #[clap(long)]
+    dry_run: bool,
+
     /// The font file to test
-    pub font: PathBuf,
+    font: PathBuf,
 }
 
-fn main() {
-    let args = Args::parse();
-    let fontdata = std::fs::read(&args.font).expect("Can't read font file");
-    let font = FontRef::new(&fontdata).expect("Can't parse font");
+#[derive(clap::Args, Debug)]
+struct DiffArgs {
+    #[clap(flatten)]
+    common: Args,
+
+    /// Only check the glyphs whose outlines differ between the two versions
+    #[clap(long)]
+    changed_only: bool,
+
+    /// The earlier version of the font
+    old: PathBuf,
+
+    /// The font file to test
+    font: PathBuf,
+}
+
+#[derive(clap::Args, Debug)]
+struct CompareArgs {
+    #[clap(flatten)]
+    common: Args,
+
+    /// Before testing, report glyphs which aren't in both fonts, and whether
+    /// the glyph order differs
+    #[clap(long)]
+    check_glyph_sets: bool,
+
+    /// The first static font
+    font_a: PathBuf,
+
+    /// The second static font
+    font_b: PathBuf,
+}
+
+#[derive(clap::Args, Debug)]
+struct StaticsArgs {
+    #[clap(flatten)]
+    common: Args,
+
+    /// Before testing, report glyphs which aren't in all the fonts, and which
+    /// fonts have a different glyph order
+    #[clap(long)]
+    check_glyph_sets: bool,
+
+    /// The variable font
+    font: PathBuf,
+
+    /// A directory of static fonts, one per named instance, named after the
+    /// instance (such as `Family-BoldItalic.ttf`)
+    statics: PathBuf,
+}
+
+#[derive(clap::Args, Debug)]
+struct CalibrateArgs {
+    /// Output JSON
+    #[clap(short, long)]
+    json: bool,
+
+    /// Calibrate from every glyph, including `.notdef` and glyphs without a
+    /// Unicode mapping
+    #[clap(long)]
+    include_all: bool,
+
+    /// The font file to calibrate from
+    font: PathBuf,
+}
+
+/// Options shared by the subcommands which check fonts
+#[derive(clap::Args, Debug)]
+pub struct Args {
+    /// Output JSON (the same as `--format json`)
+    #[clap(short, long)]
+    json: bool,
+
+    /// Output format
+    #[clap(long, arg_enum, default_value = "text")]
+    format: OutputFormat,
+
+    /// Output JSON with the problems in a flat list, each with the name of
+    /// its glyph, rather than grouped by glyph
+    #[clap(long)]
+    flat: bool,
+
+    /// Report the status of every glyph, including those which were clean or skipped
+    #[clap(long)]
+    status: bool,
+
+    /// Smoothness threshold for kink detection (default 0.1); smaller values find more kinks
+    #[clap(long)]
+    smoothness: Option<f64>,
+
+    /// Glyphs (comma-separated, `*` and `?` wildcards allowed) which are
+    /// designed as reversed copies of themselves, such as symmetric quotes.
+    /// Reversed start point and contour direction problems are not reported
+    /// for these glyphs; this is purely for noise reduction on
+    /// known-symmetric designs.
+    #[clap(long, value_delimiter = ',')]
+    expect_reversed: Vec<String>,
+
+    /// Don't report contours which are wound in opposite directions in
+    /// different masters (but are otherwise compatible)
+    #[clap(long)]
+    ignore_contour_direction: bool,
+
+    /// Add the names of the nearest named instances to master names, e.g.
+    /// `wght=700 (Bold)`
+    #[clap(long)]
+    instance_names: bool,
+
+    /// Round coordinates to multiples of this (e.g. 1 for integer font units)
+    /// before testing, so that sub-unit noise doesn't cause spurious problems
+    #[clap(long)]
+    round_to: Option<f64>,
+
+    /// Report at most this many problems for each glyph, followed by a count
+    /// of the rest
+    #[clap(long)]
+    max_problems_per_glyph: Option<usize>,
+
+    /// List each glyph's problems by contour, then node, then severity,
+    /// rather than in the order the checks found them
+    #[clap(long)]
+    sort_problems: bool,
+
+    /// Don't report wrong start points with a confidence (from 0 to 1) below
+    /// this; near-circular contours get low confidence
+    #[clap(long)]
+    min_start_point_confidence: Option<f64>,
+
+    /// Cache built glyphs in this directory between runs
+    #[clap(long)]
+    cache: Option<PathBuf>,
+
+    /// Never match contours whose centers of mass are further apart than this
+    #[clap(long)]
+    max_contour_distance: Option<f64>,
+
+    /// Don't check contours whose points move less than this in total (in
+    /// font units, default 1) between two masters
+    #[clap(long)]
+    min_contour_movement: Option<f64>,
+
+    /// Interpolation positions (comma-separated, default 0.5) at which to test contour weight
+    #[clap(long, value_delimiter = ',')]
+    weight_samples: Option<Vec<f64>>,
+
+    /// How many points either side of a contour's start point a proposed
+    /// start point may be and still be double-checked for slightly rotated,
+    /// round contours (default: a tenth of the contour's points, at least 3)
+    #[clap(long)]
+    start_point_leeway: Option<usize>,
+
+    /// Don't try to match contours between masters; test them in the given order
+    #[clap(long)]
+    no_contour_reorder: bool,
+
+    /// Use the control polygon's statistics, rather than the curves', in the
+    /// start point and weight checks
+    #[clap(long)]
+    control_statistics: bool,
+
+    /// Also test these locations (a JSON list of axis-value maps, or a CSV
+    /// file with a header row of axis tags) alongside the masters
+    #[clap(long)]
+    locations: Option<PathBuf>,
+
+    /// A JSON file mapping glyph name patterns (with `*` and `?` wildcards)
+    /// to the tolerance and kinkiness to use for matching glyphs
+    #[clap(long)]
+    tolerance_config: Option<PathBuf>,
+
+    /// Only report kinks which appear between every pair of masters, rather
+    /// than between any pair
+    #[clap(long)]
+    persistent_kinks: bool,
+
+    /// Check that smooth curve joins keep their handles aligned during interpolation
+    #[clap(long)]
+    tangent_continuity: bool,
+
+    /// Check that each curve segment bends the same way in every master
+    #[clap(long)]
+    segment_curvature: bool,
+
+    /// Check for consecutive on-curve points which meet during interpolation
+    #[clap(long)]
+    point_collisions: bool,
+
+    /// Check for contours which have points at their extrema in one master
+    /// but not the other
+    #[clap(long)]
+    extrema: bool,
+
+    /// Check for curve handles which change length drastically between masters
+    #[clap(long)]
+    handle_length: bool,
+
+    /// Check for contours whose start point is smooth in one master but a
+    /// corner in another
+    #[clap(long)]
+    start_point_type: bool,
+
+    /// Also compare the shapes of matched contours point by point, and (in
+    /// JSON output) report the Hausdorff distance between each glyph's
+    /// masters (slow)
+    #[clap(long)]
+    accurate: bool,
+
+    /// Sample how each kink develops across the interpolation, reporting
+    /// where between the masters it is at its worst
+    #[clap(long)]
+    kink_trajectories: bool,
+
+    /// Also check for contours which collapse when extrapolated this far
+    /// beyond each pair of masters (e.g. 0.5). Only useful for fonts which
+    /// are designed to be used outside their axis ranges.
+    #[clap(long)]
+    extrapolate: Option<f64>,
+
+    /// Report how much headroom each glyph has before it would fail the checks
+    #[clap(long)]
+    report_margins: bool,
+
+    /// Check that advance widths and sidebearings interpolate monotonically
+    #[clap(long)]
+    metrics: bool,
+
+    /// Check that contour centroids move smoothly across masters
+    #[clap(long)]
+    centroid_drift: bool,
+
+    /// Check that contours with the same shape in different glyphs (such as
+    /// the bowls of b and d) have the same problems
+    #[clap(long)]
+    shared_shapes: bool,
+
+    /// Check every glyph, including `.notdef` and glyphs without a Unicode mapping
+    #[clap(long)]
+    include_all: bool,
+
+    /// Unmapped glyphs (comma-separated, `*` and `?` wildcards allowed) to
+    /// check anyway
+    #[clap(long, value_delimiter = ',')]
+    include: Vec<String>,
+
+    /// Only check the glyphs which had problems in this previous JSON report
+    #[clap(long)]
+    only_from: Option<PathBuf>,
+
+    /// Only check glyph IDs from START up to (but not including) END, given as
+    /// `START:END`, so that a large font can be split across several runs
+    #[clap(long, value_parser = parse_gid_range)]
+    gid_range: Option<Range<u16>>,
+}
+
+impl Args {
+    fn statistics(&self) -> Statistics {
+        if self.control_statistics {
+            Statistics::Control
+        } else {
+            Statistics::Green
+        }
+    }
+
+    fn tolerance_config(&self) -> Option<ToleranceConfig> {
+        self.tolerance_config
+            .as_ref()
+            .map(|path| ToleranceConfig::load(path).expect("Can't read tolerance config"))
+    }
+}
+
+/// Parse a `START:END` glyph ID range
+fn parse_gid_range(s: &str) -> Result<Range<u16>, String> {
+    let (start, end) = s
+        .split_once(':')
+        .ok_or_else(|| "expected START:END".to_string())?;
+    let start: u16 = start.parse().map_err(|e| format!("bad start: {}", e))?;
+    let end: u16 = end.parse().map_err(|e| format!("bad end: {}", e))?;
+    if start >= end {
+        return Err("START must be less than END".to_string());
+    }
+    Ok(start..end)
+}
+
+/// Arrange the glyphs into runs of masters along each axis, sorted by axis value
+///
+/// Only masters which vary along a single axis (plus the default) are included.
+fn axis_sequences<'a>(
+    font: &FontRef,
+    glyphs: &'a [interpolatable::Glyph],
+    locations: &[Vec<VariationSetting>],
+) -> Vec<Vec<&'a interpolatable::Glyph>> {
+    font.axes()
+        .iter()
+        .map(|axis| {
+            let mut sequence: Vec<(f32, &interpolatable::Glyph)> = glyphs
+                .iter()
+                .zip(locations.iter())
+                .filter_map(|(glyph, loc)| match loc.as_slice() {
+                    [] => Some((axis.default_value(), glyph)),
+                    [setting] if setting.selector == axis.tag() => Some((setting.value, glyph)),
+                    _ => None,
+                })
+                .collect();
+            sequence.sort_by(|a, b| a.0.total_cmp(&b.0));
+            sequence.into_iter().map(|(_, glyph)| glyph).collect()
+        })
+        .collect()
+}
+
+fn units_per_em(font: &FontRef) -> u16 {
+    match font.head().map(|head| head.units_per_em()) {
+        Ok(upem) if upem > 0 => upem,
+        _ => {
+            eprintln!("Warning: font has no valid unitsPerEm; assuming 1000");
+            1000
+        }
+    }
+}
+
+/// Run the pairwise checks selected by `args` between two masters of a glyph
+fn test_pair(
+    before: &interpolatable::Glyph,
+    after: &interpolatable::Glyph,
+    args: &Args,
+    tolerances: GlyphTolerances,
+    upem: u16,
+) -> Vec<Problem> {
+    let options = RunTestsOptions {
+        tolerance: tolerances.tolerance,
+        kinkiness: tolerances.kinkiness,
+        smoothness: args.smoothness,
+        upem: Some(upem),
+        max_contour_distance: args.max_contour_distance,
+        min_contour_movement: args.min_contour_movement,
+        weight_samples: args.weight_samples.clone(),
+        start_point_leeway: args.start_point_leeway,
+        ignore_contour_order: args.no_contour_reorder,
+        statistics: args.statistics(),
+        check_extrema: args.extrema,
+        check_handle_length: args.handle_length,
+        check_start_point_type: args.start_point_type,
+        ..Default::default()
+    };
+    let mut problems = run_tests_with(before, after, &options);
+    if let Some(min_confidence) = args.min_start_point_confidence {
+        retain_confident_start_points(&mut problems, min_confidence);
+    }
+    if args.ignore_contour_direction {
+        problems.retain(|p| !matches!(p.details, ProblemDetails::ContourDirection));
+    }
+    if args.tangent_continuity {
+        problems.extend(test_tangent_continuity(before, after, &options));
+    }
+    if args.segment_curvature {
+        problems.extend(test_segment_curvature(before, after, &options));
+    }
+    if args.point_collisions {
+        problems.extend(test_point_collisions(before, after, &options));
+    }
+    if let Some(amount) = args.extrapolate {
+        problems.extend(test_extrapolation(before, after, amount, &options));
+    }
+    if args.accurate {
+        problems.extend(test_frechet_distance(before, after, &options));
+    }
+    if args.kink_trajectories {
+        add_kink_trajectories(
+            before,
+            after,
+            &mut problems,
+            KINK_TRAJECTORY_SAMPLES,
+            &options,
+        );
+    }
+    problems
+}
+
+/// Filter, sort and limit a glyph's problems as requested by `args`
+fn finish_problems(glyphname: &str, problems: &mut Vec<Problem>, args: &Args) {
+    if patterns::matches_any(&args.expect_reversed, glyphname) {
+        problems.retain(|p| {
+            !matches!(
+                p.details,
+                ProblemDetails::WrongStartPoint { reverse: true, .. }
+                    | ProblemDetails::ContourDirection
+            )
+        });
+    }
+    if args.sort_problems {
+        problems.sort_by(|a, b| a.cmp_for_report(b));
+    }
+    if let Some(max) = args.max_problems_per_glyph {
+        limit_problems(problems, max);
+    }
+}
+
+fn print_problems(glyphname: &str, problems: &[Problem]) {
+    println!("Problems with glyph {}:", glyphname);
+    for problem in problems.iter() {
+        println!("  {:#?}", problem);
+    }
+    if let Some(tolerance) = min_passing_tolerance(problems) {
+        // Round down, so that the suggested tolerance really passes
+        println!(
+            "  (would pass at tolerance {:.2})",
+            (tolerance * 100.0).floor() / 100.0
+        );
+    }
+}
+
+/// A problem together with the name of its glyph, for `--flat` output
+#[derive(Serialize)]
+struct FlatProblem<'a> {
+    glyph: &'a str,
+    #[serde(flatten)]
+    problem: &'a Problem,
+}
+
+/// A report's problems as JSON: grouped by glyph, or a flat list with `--flat`
+fn report_json(report: &IndexMap<String, Vec<Problem>>, args: &Args) -> serde_json::Value {
+    if !args.flat {
+        return serde_json::json!(report);
+    }
+    let flat: Vec<FlatProblem> = report
+        .iter()
+        .flat_map(|(glyph, problems)| {
+            problems
+                .iter()
+                .map(move |problem| FlatProblem { glyph, problem })
+        })
+        .collect();
+    serde_json::json!(flat)
+}
+
+/// The results of checking every glyph in a font
+struct FontReport {
+    problems: IndexMap<String, Vec<Problem>>,
+    statuses: IndexMap<String, GlyphStatus>,
+    glyphname_to_id: HashMap<String, GlyphId>,
+    locations: Vec<Vec<VariationSetting>>,
+    /// The smallest tolerance margin between any pair of masters, per glyph
+    margins: IndexMap<String, f64>,
+    /// The largest Hausdorff distance between any pair of masters, per glyph
+    distances: IndexMap<String, f64>,
+}
+
+/// The glyph IDs to check: the `--gid-range` if given, or the whole font
+fn glyph_range(font: &FontRef, args: &Args) -> Range<u16> {
+    let num_glyphs = font.maxp().expect("Can't open maxp table").num_glyphs();
+    match &args.gid_range {
+        Some(range) if range.end > num_glyphs => {
+            eprintln!(
+                "Error: glyph ID range {}:{} is outside the font, which has {} glyphs",
+                range.start, range.end, num_glyphs
+            );
+            std::process::exit(1);
+        }
+        Some(range) => range.clone(),
+        None => 0..num_glyphs,
+    }
+}
+
+/// Why a glyph is left out of a check
+enum Unselected {
+    /// The glyph isn't in one of the lists of glyphs to check
+    Filtered,
+    /// The glyph (with this name) isn't exported, and wasn't asked for
+    NotExported(String),
+}
+
+/// Whether a glyph should be checked
+///
+/// Glyphs must be in every one of the `only` lists of glyph names, and must
+/// be exported unless `--include-all` is given or they match `--include`.
+fn select_glyph(
+    font: &FontRef,
+    gid: u16,
+    args: &Args,
+    mapped: &HashSet<GlyphId>,
+    only: &[&HashSet<String>],
+) -> Result<(), Unselected> {
+    if !only.is_empty() {
+        let glyphname = glyph_name_for_id(font, gid.into()).expect("Can't get name");
+        if only.iter().any(|glyphs| !glyphs.contains(&glyphname)) {
+            return Err(Unselected::Filtered);
+        }
+    }
+    if !args.include_all && !is_exported(mapped, gid.into()) {
+        let glyphname = glyph_name_for_id(font, gid.into()).expect("Can't get name");
+        if !patterns::matches_any(&args.include, &glyphname) {
+            return Err(Unselected::NotExported(glyphname));
+        }
+    }
+    Ok(())
+}
+
+/// Run the checks selected by `args` over every glyph in the font
+///
+/// If `verbose` is set, problems are printed as they are found (unless
+/// another output format was requested).
+fn check_font(
+    font: &FontRef,
+    fontdata: &[u8],
+    args: &Args,
+    only: Option<&HashSet<String>>,
+    verbose: bool,
+) -> FontReport {
+    let verbose = verbose && args.format == OutputFormat::Text;
+    let cache = args.cache.clone().map(|dir| {
+        GlyphCache::new(dir, fontdata, args.round_to).expect("Can't create cache directory")
+    });
+    let build_glyph = |gid: GlyphId, loc: &[VariationSetting]| match &cache {
+        Some(cache) => cache.get_or_build(font, gid, loc),
+        None => interpolatable::Glyph::new_from_font_on_grid(font, gid, loc, args.round_to),
+    };
     let mut report: IndexMap<String, Vec<Problem>> = IndexMap::new();
+    let mut statuses: IndexMap<String, GlyphStatus> = IndexMap::new();
+    let mut margins: IndexMap<String, f64> = IndexMap::new();
+    let mut distances: IndexMap<String, f64> = IndexMap::new();
     let mut glyphname_to_id: HashMap<String, GlyphId> = HashMap::new();
     let mut locations: Vec<Vec<VariationSetting>> = vec![vec![]];
-    for gid in (0..font.maxp().expect("Can't open maxp table").num_glyphs()).progress() {
-        let mut default_glyph = interpolatable::Glyph::new_from_font(&font, gid.into(), &[])
-            .expect("Can't convert glyph");
-        default_glyph.master_name = "default".to_string();
+    let upem = units_per_em(font);
+    let mapped = mapped_glyphs(font);
+    let named_instances = args
+        .instance_names
+        .then(|| instances::NamedInstances::new(font));
+    let describe = |name: String, loc: &[VariationSetting]| match &named_instances {
+        Some(named_instances) => named_instances.describe(name, loc),
+        None => name,
+    };
+    let extra_locations = args
+        .locations
+        .as_ref()
+        .map(|path| locations::load_locations(path).expect("Can't read locations file"))
+        .unwrap_or_default();
+    // The first and last master of each glyph, for the shared shapes check
+    let mut shape_glyphs: Vec<(
+        String,
+        GlyphId,
+        interpolatable::Glyph,
+        interpolatable::Glyph,
+    )> = vec![];
+    let only_glyphs = args
+        .only_from
+        .as_ref()
+        .map(|path| compare::glyphs_in_report(path).expect("Can't read previous report"));
+    let only: Vec<&HashSet<String>> = only_glyphs.iter().chain(only).collect();
+    let tolerance_config = args.tolerance_config();
+    for gid in glyph_range(font, args).progress() {
+        match select_glyph(font, gid, args, &mapped, &only) {
+            Ok(()) => {}
+            Err(Unselected::NotExported(glyphname)) => {
+                if args.status {
+                    statuses.insert(
+                        glyphname,
+                        GlyphStatus::Skipped {
+                            reason: "not exported".to_string(),
+                        },
+                    );
+                }
+                continue;
+            }
+            Err(Unselected::Filtered) => continue,
+        }
+        let mut default_glyph = build_glyph(gid.into(), &[]).expect("Can't convert glyph");
+        default_glyph.master_name = describe("default".to_string(), &[]);
         default_glyph.master_index = 0;
-        if let Ok(variations) = glyph_variations(&font, gid.into()) {
+        let variations = glyph_variations(font, gid.into());
+        let status = match &variations {
+            Err(_) => GlyphStatus::Skipped {
+                reason: "no variation data".to_string(),
+            },
+            Ok(variations) if variations.is_empty() => GlyphStatus::Skipped {
+                reason: "no variations".to_string(),
+            },
+            Ok(_) => GlyphStatus::Clean,
+        };
+        if let Ok(mut variations) = variations {
+            // User-specified locations are tested as if they were extra masters
+            if !variations.is_empty() {
+                for loc in extra_locations.iter() {
+                    if !variations.contains(loc) {
+                        variations.push(loc.clone());
+                    }
+                }
+            }
             let variation_glyphs = variations.iter().map(|loc| {
-                let mut glyph = interpolatable::Glyph::new_from_font(&font, gid.into(), loc)
-                    .expect("Couldn't convert glyph");
-                glyph.master_name = loc
-                    .iter()
-                    .map(|v| format!("{}={}", v.selector, v.value))
-                    .collect::<Vec<_>>()
-                    .join(",");
+                let mut glyph = build_glyph(gid.into(), loc).expect("Couldn't convert glyph");
+                glyph.master_name = describe(
+                    loc.iter()
+                        .map(|v| format!("{}={}", v.selector, v.value))
+                        .collect::<Vec<_>>()
+                        .join(","),
+                    loc,
+                );
                 if !locations.contains(loc) {
                     locations.push(loc.clone());
                 }
@@ -61,44 +731,625 @@ fn main() {
             let to_test = std::iter::once(default_glyph)
                 .chain(variation_glyphs)
                 .collect::<Vec<_>>();
-            for pair in to_test.windows(2) {
-                if let [before, after] = pair {
-                    // println!("Testing {} vs {}", after.master_name, before.master_name);
-                    let problems = run_tests(
-                        before,
-                        after,
-                        None,
-                        None,
-                        Some(font.head().unwrap().units_per_em()),
-                    );
-                    if !problems.is_empty() {
-                        let glyphname =
-                            glyph_name_for_id(&font, gid.into()).expect("Can't get name");
-                        if !args.json {
-                            println!("Problems with glyph {}:", &glyphname);
-                            for problem in problems.iter() {
-                                println!("  {:#?}", problem);
-                            }
+            let tolerances = tolerance_config
+                .as_ref()
+                .map(|config| {
+                    config.for_glyph(&glyph_name_for_id(font, gid.into()).expect("Can't get name"))
+                })
+                .unwrap_or_default();
+            let mut pair_problems = to_test
+                .windows(2)
+                .filter_map(|pair| match pair {
+                    [before, after] => Some(test_pair(before, after, args, tolerances, upem)),
+                    _ => None,
+                })
+                .collect::<Vec<_>>();
+            if args.report_margins {
+                let margin = to_test
+                    .windows(2)
+                    .filter_map(|pair| tolerance_margin(&pair[0], &pair[1], None))
+                    .reduce(f64::min);
+                if let Some(margin) = margin {
+                    let glyphname = glyph_name_for_id(font, gid.into()).expect("Can't get name");
+                    margins.insert(glyphname, margin);
+                }
+            }
+            if args.accurate {
+                let distance = to_test
+                    .windows(2)
+                    .filter_map(|pair| hausdorff_distance(&pair[0], &pair[1]))
+                    .reduce(f64::max);
+                if let Some(distance) = distance {
+                    let glyphname = glyph_name_for_id(font, gid.into()).expect("Can't get name");
+                    distances.insert(glyphname, distance);
+                }
+            }
+            if args.persistent_kinks {
+                retain_persistent_kinks(&mut pair_problems);
+            }
+            // Filter, sort and limit the problems from every master pair
+            // together, so that the per-glyph limit applies to the glyph
+            let mut problems: Vec<Problem> = pair_problems.into_iter().flatten().collect();
+            if !problems.is_empty() {
+                let glyphname = glyph_name_for_id(font, gid.into()).expect("Can't get name");
+                finish_problems(&glyphname, &mut problems, args);
+                if !problems.is_empty() {
+                    if verbose {
+                        print_problems(&glyphname, &problems);
+                    }
+                    glyphname_to_id.insert(glyphname.clone(), gid.into());
+                    report.entry(glyphname).or_default().extend(problems);
+                }
+            }
+            let identical: Vec<Problem> = to_test
+                .iter()
+                .skip(1)
+                .filter_map(|master| test_identical_master(&to_test[0], master))
+                .collect();
+            if !identical.is_empty() {
+                let glyphname = glyph_name_for_id(font, gid.into()).expect("Can't get name");
+                if verbose {
+                    for problem in identical.iter() {
+                        println!(
+                            "Glyph {}: master {} is identical to the default",
+                            &glyphname, problem.master_2_name
+                        );
+                    }
+                }
+                glyphname_to_id.insert(glyphname.clone(), gid.into());
+                report.entry(glyphname).or_default().extend(identical);
+            }
+            if args.metrics || args.centroid_drift {
+                let to_test_locations = std::iter::once(vec![])
+                    .chain(variations.iter().cloned())
+                    .collect::<Vec<_>>();
+                let mut problems = vec![];
+                for sequence in axis_sequences(font, &to_test, &to_test_locations).iter() {
+                    if args.metrics {
+                        problems.extend(test_metrics(sequence));
+                    }
+                    if args.centroid_drift {
+                        problems.extend(test_centroid_drift(sequence));
+                    }
+                }
+                if !problems.is_empty() {
+                    let glyphname = glyph_name_for_id(font, gid.into()).expect("Can't get name");
+                    if verbose {
+                        println!("Multi-master problems with glyph {}:", &glyphname);
+                        for problem in problems.iter() {
+                            println!("  {:#?}", problem);
                         }
-                        glyphname_to_id.insert(glyphname.clone(), gid.into());
-                        report.insert(glyphname.clone(), problems);
                     }
+                    glyphname_to_id.insert(glyphname.clone(), gid.into());
+                    report.entry(glyphname).or_default().extend(problems);
                 }
             }
+            if args.shared_shapes {
+                let mut masters = to_test.into_iter();
+                if let (Some(first), Some(last)) = (masters.next(), masters.last()) {
+                    let glyphname = glyph_name_for_id(font, gid.into()).expect("Can't get name");
+                    shape_glyphs.push((glyphname, gid.into(), first, last));
+                }
+            }
+        }
+        if args.status {
+            let glyphname = glyph_name_for_id(font, gid.into()).expect("Can't get name");
+            let status = if report.contains_key(&glyphname) {
+                GlyphStatus::Checked
+            } else {
+                status
+            };
+            statuses.insert(glyphname, status);
         }
     }
+    if args.shared_shapes {
+        let pair_reports: Vec<GlyphPairReport> = shape_glyphs
+            .iter()
+            .map(|(glyphname, _, glyph_a, glyph_b)| GlyphPairReport {
+                name: glyphname,
+                glyph_a,
+                glyph_b,
+                problems: report.get(glyphname).map(Vec::as_slice).unwrap_or_default(),
+            })
+            .collect();
+        let problems = test_shared_shapes(&pair_reports);
+        for (glyphname, problem) in problems {
+            if verbose {
+                println!("Shared shape problem with glyph {}:", &glyphname);
+                println!("  {:#?}", problem);
+            }
+            if let Some((_, gid, _, _)) = shape_glyphs.iter().find(|g| g.0 == glyphname) {
+                glyphname_to_id.insert(glyphname.clone(), *gid);
+            }
+            if let Some(status) = statuses.get_mut(&glyphname) {
+                *status = GlyphStatus::Checked;
+            }
+            report.entry(glyphname).or_default().push(problem);
+        }
+    }
+
+    FontReport {
+        problems: report,
+        statuses,
+        glyphname_to_id,
+        locations,
+        margins,
+        distances,
+    }
+}
+
+/// Check each glyph of a static font against the glyph of the same name in another
+///
+/// Glyphs which only appear in one of the fonts are skipped.
+fn compare_fonts(
+    font_a: &FontRef,
+    location_a: &[VariationSetting],
+    font_b: &FontRef,
+    names: (String, String),
+    args: &Args,
+    verbose: bool,
+) -> IndexMap<String, Vec<Problem>> {
+    let verbose = verbose && args.format == OutputFormat::Text;
+    let upem = units_per_em(font_a);
+    let mapped = mapped_glyphs(font_a);
+    let tolerance_config = args.tolerance_config();
+    let num_glyphs_b = font_b.maxp().expect("Can't open maxp table").num_glyphs();
+    let gids_b: HashMap<String, GlyphId> = (0..num_glyphs_b)
+        .filter_map(|gid| Some((glyph_name_for_id(font_b, gid.into()).ok()?, gid.into())))
+        .collect();
+    let num_glyphs = font_a.maxp().expect("Can't open maxp table").num_glyphs();
+    let mut report: IndexMap<String, Vec<Problem>> = IndexMap::new();
+    for gid in (0..num_glyphs).progress() {
+        let glyphname = glyph_name_for_id(font_a, gid.into()).expect("Can't get name");
+        if !args.include_all
+            && !is_exported(&mapped, gid.into())
+            && !patterns::matches_any(&args.include, &glyphname)
+        {
+            continue;
+        }
+        let Some(gid_b) = gids_b.get(&glyphname) else {
+            continue;
+        };
+        let mut glyph_a = interpolatable::Glyph::new_from_font_on_grid(
+            font_a,
+            gid.into(),
+            location_a,
+            args.round_to,
+        )
+        .expect("Can't convert glyph");
+        glyph_a.master_name = names.0.clone();
+        let mut glyph_b =
+            interpolatable::Glyph::new_from_font_on_grid(font_b, *gid_b, &[], args.round_to)
+                .expect("Can't convert glyph");
+        glyph_b.master_name = names.1.clone();
+        glyph_b.master_index = 1;
+        let tolerances = tolerance_config
+            .as_ref()
+            .map(|config| config.for_glyph(&glyphname))
+            .unwrap_or_default();
+        let mut problems = test_pair(&glyph_a, &glyph_b, args, tolerances, upem);
+        finish_problems(&glyphname, &mut problems, args);
+        if !problems.is_empty() {
+            if verbose {
+                print_problems(&glyphname, &problems);
+            }
+            report.insert(glyphname, problems);
+        }
+    }
+    report
+}
 
+/// Check each glyph's named instances against the interpolation of its masters
+///
+/// Returns the problems for each instance, by instance name then glyph name.
+fn check_named_instances(
+    font: &FontRef,
+    args: &Args,
+) -> IndexMap<String, IndexMap<String, Vec<Problem>>> {
+    let mapped = mapped_glyphs(font);
+    let mut report: IndexMap<String, IndexMap<String, Vec<Problem>>> = IndexMap::new();
+    for gid in glyph_range(font, args) {
+        let glyphname = glyph_name_for_id(font, gid.into()).expect("Can't get name");
+        if !args.include_all
+            && !is_exported(&mapped, gid.into())
+            && !patterns::matches_any(&args.include, &glyphname)
+        {
+            continue;
+        }
+        for (instance, problems) in test_named_instances(font, gid.into()) {
+            report
+                .entry(instance)
+                .or_default()
+                .insert(glyphname.clone(), problems);
+        }
+    }
+    report
+}
+
+/// Print the problems found for each named instance, by instance name then glyph name
+fn print_instance_report(
+    report: &IndexMap<String, IndexMap<String, Vec<Problem>>>,
+    format: OutputFormat,
+) {
+    if format == OutputFormat::Json {
+        println!("{}", serde_json::to_string_pretty(report).unwrap());
+        return;
+    }
+    for (instance, glyphs) in report {
+        if format == OutputFormat::Markdown {
+            print!(
+                "\n## Named instance {}\n\n{}",
+                instance,
+                markdown::markdown_report(glyphs)
+            );
+            continue;
+        }
+        println!("Named instance {}:", instance);
+        for (glyphname, problems) in glyphs {
+            println!("  Problems with glyph {}:", glyphname);
+            for problem in problems {
+                println!("    {:#?}", problem);
+            }
+        }
+    }
+}
+
+/// The key used to match a named instance to a static font file
+///
+/// Both are reduced to lowercase letters and digits, and only the style part
+/// of a file name (after the last hyphen) is used, so that an instance named
+/// "Bold Italic" matches `Family-BoldItalic.ttf`.
+fn instance_key(name: &str) -> String {
+    name.chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .collect::<String>()
+        .to_ascii_lowercase()
+}
+
+/// Find the static font for each of a variable font's named instances
+///
+/// Returns each instance's name, its location and the path of the matching
+/// font in `dir`. Instances without a matching font are reported and skipped.
+fn instance_statics(
+    font: &FontRef,
+    dir: &std::path::Path,
+) -> Vec<(String, Vec<VariationSetting>, PathBuf)> {
+    let statics: HashMap<String, PathBuf> = std::fs::read_dir(dir)
+        .expect("Can't read directory of static fonts")
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| {
+            path.extension().is_some_and(|ext| {
+                ext.eq_ignore_ascii_case("ttf") || ext.eq_ignore_ascii_case("otf")
+            })
+        })
+        .filter_map(|path| {
+            let stem = path.file_stem()?.to_string_lossy().to_string();
+            let style = stem.rsplit('-').next().unwrap_or(&stem);
+            Some((instance_key(style), path))
+        })
+        .collect();
+    let axes = font.axes();
+    let mut matched = vec![];
+    for instance in font.named_instances().iter() {
+        let Some(name) = font
+            .localized_strings(instance.subfamily_name_id())
+            .english_or_first()
+            .map(|s| s.to_string())
+        else {
+            continue;
+        };
+        let Some(path) = statics.get(&instance_key(&name)) else {
+            eprintln!("Warning: no static font for instance {}", name);
+            continue;
+        };
+        let location: Vec<VariationSetting> = axes
+            .iter()
+            .zip(instance.user_coords())
+            .map(|(axis, value)| (axis.tag(), value).into())
+            .collect();
+        matched.push((name, location, path.clone()));
+    }
+    matched
+}
+
+/// Suggest tolerances from every pair of adjacent masters of every glyph
+fn calibrate_font(font: &FontRef, include_all: bool) -> Tolerances {
+    let mapped = mapped_glyphs(font);
+    let num_glyphs = font.maxp().expect("Can't open maxp table").num_glyphs();
+    let mut pairs = vec![];
+    for gid in (0..num_glyphs).progress() {
+        if !include_all && !is_exported(&mapped, gid.into()) {
+            continue;
+        }
+        let Ok(variations) = glyph_variations(font, gid.into()) else {
+            continue;
+        };
+        let locations = std::iter::once(vec![])
+            .chain(variations)
+            .collect::<Vec<_>>();
+        let build_glyph = |loc: &[VariationSetting]| {
+            interpolatable::Glyph::new_from_font(font, gid.into(), loc)
+                .expect("Can't convert glyph")
+        };
+        for pair in locations.windows(2) {
+            pairs.push((build_glyph(&pair[0]), build_glyph(&pair[1])));
+        }
+    }
+    calibrate(&pairs)
+}
+
+/// The file name of a font, for labelling its glyphs as masters
+fn file_label(path: &std::path::Path) -> String {
+    path.file_name()
+        .map(|f| f.to_string_lossy().to_string())
+        .unwrap_or_else(|| path.display().to_string())
+}
+
+fn main() {
+    let mut cli = Cli::parse();
+    if let Command::Check(CheckArgs { common, .. })
+    | Command::Diff(DiffArgs { common, .. })
+    | Command::Compare(CompareArgs { common, .. })
+    | Command::Statics(StaticsArgs { common, .. }) = &mut cli.command
+    {
+        if common.json || common.flat {
+            common.format = OutputFormat::Json;
+        }
+    }
+    match cli.command {
+        Command::Check(args) => run_check(args),
+        Command::Diff(args) => run_diff(args),
+        Command::Compare(args) => run_compare(args),
+        Command::Statics(args) => run_statics(args),
+        Command::Calibrate(args) => run_calibrate(args),
+    }
+}
+
+fn run_diff(args: DiffArgs) {
+    let fontdata = std::fs::read(&args.font).expect("Can't read font file");
+    let font = FontRef::new(&fontdata).expect("Can't parse font");
+    let old_fontdata = std::fs::read(&args.old).expect("Can't read font file");
+    let old_font = FontRef::new(&old_fontdata).expect("Can't parse font");
+    let changed = args
+        .changed_only
+        .then(|| compare::changed_glyphs(&old_font, &font));
+    let old_report = check_font(
+        &old_font,
+        &old_fontdata,
+        &args.common,
+        changed.as_ref(),
+        false,
+    );
+    let new_report = check_font(&font, &fontdata, &args.common, changed.as_ref(), false);
+    let changes = compare::compare_reports(&old_report.problems, &new_report.problems);
+    if args.common.format == OutputFormat::Json {
+        println!("{}", serde_json::to_string_pretty(&changes).unwrap());
+    } else {
+        changes.print();
+    }
+}
+
+fn run_compare(args: CompareArgs) {
+    let fontdata_a = std::fs::read(&args.font_a).expect("Can't read font file");
+    let font_a = FontRef::new(&fontdata_a).expect("Can't parse font");
+    let fontdata_b = std::fs::read(&args.font_b).expect("Can't read font file");
+    let font_b = FontRef::new(&fontdata_b).expect("Can't parse font");
+    let names = (file_label(&args.font_a), file_label(&args.font_b));
+    if args.check_glyph_sets {
+        compare::GlyphSetReport::new(&[(names.0.clone(), &font_a), (names.1.clone(), &font_b)])
+            .warn();
+    }
+    let report = compare_fonts(&font_a, &[], &font_b, names, &args.common, true);
+    match args.common.format {
+        OutputFormat::Json => println!(
+            "{}",
+            serde_json::to_string_pretty(&report_json(&report, &args.common)).unwrap()
+        ),
+        OutputFormat::Markdown => print!("{}", markdown::markdown_report(&report)),
+        OutputFormat::Text => {}
+    }
+}
+
+fn run_statics(args: StaticsArgs) {
+    let fontdata = std::fs::read(&args.font).expect("Can't read font file");
+    let font = FontRef::new(&fontdata).expect("Can't parse font");
+    let statics: Vec<_> = instance_statics(&font, &args.statics)
+        .into_iter()
+        .map(|(instance, location, path)| {
+            let static_data = std::fs::read(&path).expect("Can't read font file");
+            (instance, location, path, static_data)
+        })
+        .collect();
+    if args.check_glyph_sets {
+        let fonts: Vec<(String, FontRef)> = std::iter::once((file_label(&args.font), font.clone()))
+            .chain(statics.iter().map(|(_, _, path, static_data)| {
+                let static_font = FontRef::new(static_data).expect("Can't parse font");
+                (file_label(path), static_font)
+            }))
+            .collect();
+        let fonts: Vec<(String, &FontRef)> = fonts
+            .iter()
+            .map(|(label, font)| (label.clone(), font))
+            .collect();
+        compare::GlyphSetReport::new(&fonts).warn();
+    }
+    let mut report: IndexMap<String, IndexMap<String, Vec<Problem>>> = IndexMap::new();
+    for (instance, location, path, static_data) in statics {
+        let static_font = FontRef::new(&static_data).expect("Can't parse font");
+        let names = (format!("{} (variable)", instance), file_label(&path));
+        let problems = compare_fonts(&font, &location, &static_font, names, &args.common, false);
+        if !problems.is_empty() {
+            report.insert(instance, problems);
+        }
+    }
+    print_instance_report(&report, args.common.format);
+}
+
+fn run_calibrate(args: CalibrateArgs) {
+    let fontdata = std::fs::read(&args.font).expect("Can't read font file");
+    let font = FontRef::new(&fontdata).expect("Can't parse font");
+    let tolerances = calibrate_font(&font, args.include_all);
     if args.json {
-        println!("{}", serde_json::to_string_pretty(&report).unwrap());
+        println!("{}", serde_json::to_string_pretty(&tolerances).unwrap());
+    } else {
+        println!("tolerance: {:.3}", tolerances.tolerance);
+        println!("kinkiness: {:.3}", tolerances.kinkiness);
+    }
+}
+
+fn run_check(check_args: CheckArgs) {
+    let args = &check_args.common;
+    let fontdata = std::fs::read(&check_args.font).expect("Can't read font file");
+    let font = FontRef::new(&fontdata).expect("Can't parse font");
+
+    if check_args.dry_run {
+        let dry_run = dryrun::DryRun::new(&font, args);
+        if args.format == OutputFormat::Json {
+            println!("{}", serde_json::to_string_pretty(&dry_run).unwrap());
+        } else {
+            dry_run.print();
+        }
+        return;
+    }
+
+    if !check_args.family.is_empty() {
+        let mut family = family::FamilyReport::default();
+        for path in std::iter::once(&check_args.font).chain(check_args.family.iter()) {
+            let fontdata = std::fs::read(path).expect("Can't read font file");
+            let font = FontRef::new(&fontdata).expect("Can't parse font");
+            let report = check_font(&font, &fontdata, args, None, false);
+            family.add(&font, path, report.problems);
+        }
+        match args.format {
+            OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&family).unwrap()),
+            OutputFormat::Markdown => print!("{}", family.markdown()),
+            OutputFormat::Text => family.print(),
+        }
+        return;
+    }
+
+    let instance_report = check_args
+        .named_instances
+        .then(|| check_named_instances(&font, args));
+
+    let style_axes = if check_args.by_axis {
+        let style_axes = stat::StyleAxes::new(&font);
+        if style_axes.is_none() {
+            eprintln!("Warning: font has no STAT table, so problems can't be grouped by axis");
+        }
+        style_axes
+    } else {
+        None
+    };
+
+    let FontReport {
+        problems: report,
+        statuses,
+        glyphname_to_id,
+        locations,
+        margins,
+        distances,
+    } = check_font(&font, &fontdata, args, None, style_axes.is_none());
+
+    if let Some(style_axes) = &style_axes {
+        let grouped = style_axes.group(&report, &locations);
+        match args.format {
+            OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&grouped).unwrap()),
+            OutputFormat::Markdown => print!("{}", grouped.markdown()),
+            OutputFormat::Text => grouped.print(),
+        }
+    } else if args.format == OutputFormat::Json {
+        if args.status
+            || args.report_margins
+            || args.accurate
+            || check_args.with_summary
+            || instance_report.is_some()
+        {
+            let mut output = serde_json::json!({ "problems": report_json(&report, args) });
+            if check_args.with_summary {
+                let mut summary: IndexMap<String, usize> = IndexMap::new();
+                for problem in report.values().flatten() {
+                    *summary.entry(problem.problem_type()).or_default() += 1;
+                }
+                output["summary"] = serde_json::json!(summary);
+            }
+            if args.status {
+                output["status"] = serde_json::json!(statuses);
+            }
+            if args.report_margins {
+                output["margins"] = serde_json::json!(margins);
+            }
+            if args.accurate {
+                output["hausdorff"] = serde_json::json!(distances);
+            }
+            if let Some(instance_report) = &instance_report {
+                output["instances"] = serde_json::json!(instance_report);
+            }
+            println!("{}", serde_json::to_string_pretty(&output).unwrap());
+        } else {
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&report_json(&report, args)).unwrap()
+            );
+        }
+    } else if args.format == OutputFormat::Markdown {
+        print!("{}", markdown::markdown_report(&report));
+    } else if args.status {
+        let count =
+            |wanted: fn(&GlyphStatus) -> bool| statuses.values().filter(|s| wanted(s)).count();
+        println!(
+            "{} glyphs: {} with problems, {} clean, {} skipped",
+            statuses.len(),
+            count(|s| matches!(s, GlyphStatus::Checked)),
+            count(|s| matches!(s, GlyphStatus::Clean)),
+            count(|s| matches!(s, GlyphStatus::Skipped { .. })),
+        );
+        for (glyphname, status) in statuses.iter() {
+            if let GlyphStatus::Skipped { reason } = status {
+                println!("  Skipped {}: {}", glyphname, reason);
+            }
+        }
+    }
+    if let Some(instance_report) = &instance_report {
+        // The JSON output is included in the report above
+        if args.format != OutputFormat::Json {
+            print_instance_report(instance_report, args.format);
+        }
+    }
+    if args.format == OutputFormat::Text && args.report_margins {
+        println!("Tolerance margins, smallest first:");
+        for (glyphname, margin) in margins.iter().sorted_by(|a, b| a.1.total_cmp(b.1)) {
+            println!("  {}: {:.3}", glyphname, margin);
+        }
+    }
+
+    if let Some(svg_dir) = &check_args.svg_dir {
+        svg::SvgWriter::new(&font, &locations, &glyphname_to_id)
+            .write_all(svg_dir, &report)
+            .expect("Can't write SVG files");
     }
 
-    if let Some(pdf) = args.pdf {
+    if let Some(pdf) = check_args.pdf {
         let surface =
             cairo::PdfSurface::new(InterpolatablePlot::WIDTH, InterpolatablePlot::HEIGHT, &pdf)
                 .expect("Can't create PDF");
-        let mut plot =
-            InterpolatablePlot::new(&surface, font, &locations, glyphname_to_id, None, None);
-        plot.add_title_page(&[args.font], None, None, None)
+        let mut plot = InterpolatablePlot::new(
+            &surface,
+            font,
+            &locations,
+            glyphname_to_id,
+            None,
+            None,
+            check_args.lang.clone(),
+        );
+        plot.set_reference_overlay(check_args.reference_overlay);
+        plot.set_thumbnails(check_args.thumbnails);
+        plot.set_contour_options(RunTestsOptions {
+            max_contour_distance: args.max_contour_distance,
+            ignore_contour_order: args.no_contour_reorder,
+            ..Default::default()
+        });
+        plot.add_title_page(&[check_args.font], None, None, None)
             .expect("Can't add title page");
         if !report.is_empty() {
             plot.add_summary(&report).expect("Can't add summary");
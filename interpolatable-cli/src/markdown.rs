@@ -0,0 +1,40 @@
+use indexmap::IndexMap;
+use interpolatable::Problem;
+
+fn cell(text: &str) -> String {
+    text.replace('|', "\\|")
+}
+
+fn optional_cell<T: ToString>(value: Option<T>) -> String {
+    value.map(|v| v.to_string()).unwrap_or_default()
+}
+
+/// Format the report as a Markdown table, one row per problem
+///
+/// The glyph name is only given on the first row of each glyph's problems.
+pub(crate) fn markdown_report(report: &IndexMap<String, Vec<Problem>>) -> String {
+    let mut output = String::from(
+        "| Glyph | Problem | Masters | Contour | Node | Badness |\n\
+         |-------|---------|---------|---------|------|---------|\n",
+    );
+    for (glyphname, problems) in report {
+        for (ix, problem) in problems.iter().enumerate() {
+            let glyph = if ix == 0 {
+                cell(glyphname)
+            } else {
+                String::new()
+            };
+            output.push_str(&format!(
+                "| {} | {} | {} / {} | {} | {} | {} |\n",
+                glyph,
+                cell(&problem.details.to_string()),
+                cell(&problem.master_1_name),
+                cell(&problem.master_2_name),
+                optional_cell(problem.contour),
+                optional_cell(problem.node),
+                optional_cell(problem.badness.map(|b| format!("{:.3}", b.value()))),
+            ));
+        }
+    }
+    output
+}
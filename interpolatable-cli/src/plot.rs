@@ -5,9 +5,9 @@ use std::{
 
 use cairo::{Context, Error, FontSlant, FontWeight, Surface};
 use indexmap::IndexMap;
-use interpolatable::{BezGlyph, Glyph, Problem};
+use interpolatable::{contour_mapping, BezGlyph, Glyph, Problem, ProblemDetails, RunTestsOptions};
 use itertools::Itertools;
-use kurbo::{Rect, Shape};
+use kurbo::{BezPath, Rect};
 use skrifa::{
     prelude::LocationRef, setting::VariationSetting, string::StringId, FontRef, GlyphId,
     MetadataProvider,
@@ -24,6 +24,10 @@ pub(crate) struct InterpolatablePlot<'a> {
     width: f64,
     height: f64,
     page_number: usize,
+    language: Option<String>,
+    reference_overlay: bool,
+    thumbnails: bool,
+    contour_options: RunTestsOptions<'a>,
 }
 
 impl<'a> InterpolatablePlot<'a> {
@@ -34,6 +38,7 @@ impl<'a> InterpolatablePlot<'a> {
         glyphname_to_id: HashMap<String, GlyphId>,
         width: Option<f64>,
         height: Option<f64>,
+        language: Option<String>,
     ) -> Self {
         let width = width.unwrap_or(InterpolatablePlot::WIDTH);
         let height = height.unwrap_or(InterpolatablePlot::HEIGHT);
@@ -47,9 +52,29 @@ impl<'a> InterpolatablePlot<'a> {
             width,
             height,
             page_number,
+            language,
+            reference_overlay: false,
+            thumbnails: false,
+            contour_options: RunTestsOptions::default(),
         }
     }
 
+    /// Draw the default master's outline faintly behind each other outline
+    pub fn set_reference_overlay(&mut self, reference_overlay: bool) {
+        self.reference_overlay = reference_overlay;
+    }
+
+    /// Show small outlines of the glyph at every master alongside each problem
+    pub fn set_thumbnails(&mut self, thumbnails: bool) {
+        self.thumbnails = thumbnails;
+    }
+
+    /// Match up the contours of the masters as the checks did, so that
+    /// contour order problems color matching contours alike
+    pub fn set_contour_options(&mut self, contour_options: RunTestsOptions<'a>) {
+        self.contour_options = contour_options;
+    }
+
     fn panel_width(&self) -> f64 {
         self.width / 2.0 - 3.0 * InterpolatablePlot::PAD
     }
@@ -67,6 +92,7 @@ impl<'a> InterpolatablePlot<'a> {
     const BORDER_COLOR: (f64, f64, f64) = (0.9, 0.9, 0.9);
     const BORDER_WIDTH: f64 = 0.5;
     const FILL_COLOR: (f64, f64, f64) = (0.8, 0.8, 0.8);
+    const REFERENCE_COLOR: (f64, f64, f64, f64) = (0.0, 0.4, 0.8, 0.3);
     const STROKE_COLOR: (f64, f64, f64) = (0.1, 0.1, 0.1);
     const STROKE_WIDTH: f64 = 1.0;
     const ONCURVE_NODE_COLOR: (f64, f64, f64, f64) = (0.0, 0.8, 0.0, 0.7);
@@ -94,6 +120,8 @@ impl<'a> InterpolatablePlot<'a> {
         (0.0, 1.0, 1.0, 1.0),
     ];
     const CONTOUR_ALPHA: f64 = 0.5;
+    const THUMBNAILS_PER_ROW: usize = 6;
+    const THUMBNAIL_HIGHLIGHT_COLOR: (f64, f64, f64) = (1.0, 0.0, 0.0);
     const WEIGHT_ISSUE_CONTOUR_COLOR: (f64, f64, f64, f64) = (0.0, 0.0, 0.0, 0.4);
     const NO_ISSUES_LABEL: &'static str = "Your font's good! Have a cupcake...";
     const NO_ISSUES_LABEL_COLOR: (f64, f64, f64) = (0.0, 0.5, 0.0);
@@ -192,6 +220,7 @@ impl<'a> InterpolatablePlot<'a> {
 
             y = self.draw_font_sha(file, x, y, width)?;
             y = self.draw_font_family_name(file, x, y, width)?;
+            y = self.draw_axes_summary(file, x, y, width)?;
         }
 
         self.draw_legend(show_tolerance, tolerance, kinkiness)?;
@@ -219,20 +248,11 @@ impl<'a> InterpolatablePlot<'a> {
         let mut y = y;
         let data = std::fs::read(file).unwrap();
         let font = FontRef::new(&data).unwrap();
-        let family_name = font
-            .localized_strings(StringId::WWS_FAMILY_NAME)
-            .english_or_first()
-            .or_else(|| {
-                font.localized_strings(StringId::TYPOGRAPHIC_FAMILY_NAME)
-                    .english_or_first()
-            })
-            .or_else(|| {
-                font.localized_strings(StringId::FAMILY_NAME)
-                    .english_or_first()
-            });
-        let version = font
-            .localized_strings(StringId::VERSION_STRING)
-            .english_or_first();
+        let family_name = self
+            .preferred_string(&font, StringId::WWS_FAMILY_NAME)
+            .or_else(|| self.preferred_string(&font, StringId::TYPOGRAPHIC_FAMILY_NAME))
+            .or_else(|| self.preferred_string(&font, StringId::FAMILY_NAME));
+        let version = self.preferred_string(&font, StringId::VERSION_STRING);
         for (name, label) in [("Family", family_name), ("Version", version)] {
             if let Some(label) = label {
                 self.draw_label(
@@ -251,6 +271,64 @@ impl<'a> InterpolatablePlot<'a> {
         Ok(y)
     }
 
+    /// List the font's variation axes and their ranges, and how many named instances it has
+    fn draw_axes_summary(&self, file: &Path, x: f64, y: f64, width: f64) -> Result<f64, Error> {
+        let mut y = y;
+        let data = std::fs::read(file).unwrap();
+        let font = FontRef::new(&data).unwrap();
+        let axes = font.axes();
+        if axes.is_empty() {
+            return Ok(y);
+        }
+        let mut lines = axes
+            .iter()
+            .map(|axis| {
+                format!(
+                    "{}: {}–{} (default {})",
+                    axis.tag(),
+                    axis.min_value(),
+                    axis.max_value(),
+                    axis.default_value()
+                )
+            })
+            .collect::<Vec<_>>();
+        lines.push(format!("Named instances: {}", font.named_instances().len()));
+        for line in lines {
+            self.draw_label(
+                &line,
+                x + InterpolatablePlot::PAD,
+                y,
+                None,
+                0.5,
+                false,
+                Some(width),
+                InterpolatablePlot::FONT_SIZE,
+            )?;
+            y += InterpolatablePlot::FONT_SIZE + InterpolatablePlot::PAD;
+        }
+        Ok(y)
+    }
+
+    /// Find a name table string in the user's preferred language, falling back
+    /// to English and then to the first string available
+    fn preferred_string(&self, font: &FontRef, id: StringId) -> Option<String> {
+        if let Some(language) = &self.language {
+            let preferred = font.localized_strings(id).find(|s| {
+                s.language().is_some_and(|l| {
+                    l.eq_ignore_ascii_case(language)
+                        || l.to_ascii_lowercase()
+                            .starts_with(&format!("{}-", language.to_ascii_lowercase()))
+                })
+            });
+            if let Some(preferred) = preferred {
+                return Some(preferred.to_string());
+            }
+        }
+        font.localized_strings(id)
+            .english_or_first()
+            .map(|s| s.to_string())
+    }
+
     fn draw_legend(
         &self,
         show_tolerance: Option<bool>,
@@ -268,7 +346,7 @@ impl<'a> InterpolatablePlot<'a> {
 
         if let Some(true) = show_tolerance {
             self.draw_label(
-                "Tolerance: badness; closer to zero the worse",
+                "Badness: from 0 to 1; higher is worse",
                 xxx,
                 y,
                 None,
@@ -618,13 +696,13 @@ impl<'a> InterpolatablePlot<'a> {
             InterpolatablePlot::TITLE_FONT_SIZE,
         )?;
 
-        let tolerance = problems
+        let badness = problems
             .iter()
-            .map(|p| p.tolerance.unwrap_or(1.0))
-            .fold(1.0f64, |a, b| a.min(b));
-        if tolerance < 1.0 {
+            .filter_map(|p| p.badness)
+            .fold(0.0f64, |a, b| a.max(b.value()));
+        if badness > 0.0 {
             self.draw_label(
-                &format!("Tolerance: {}", tolerance),
+                &format!("Badness: {}", badness),
                 x,
                 y,
                 None,
@@ -647,6 +725,10 @@ impl<'a> InterpolatablePlot<'a> {
         )?;
         y += InterpolatablePlot::FONT_SIZE + pad * 2.0;
 
+        let contour_colors = problems
+            .iter()
+            .find(|problem| matches!(problem.details, ProblemDetails::ContourOrder { .. }))
+            .and_then(|problem| self.contour_colors(glyphname, problem));
         let mut scales = vec![];
         for (which, &master_idx) in [problems[0].master_1_index, problems[0].master_2_index]
             .iter()
@@ -669,8 +751,22 @@ impl<'a> InterpolatablePlot<'a> {
             )?;
             y += InterpolatablePlot::FONT_SIZE + pad;
             if let Some(location) = &self.locations.get(master_idx) {
-                scales
-                    .push(self.draw_glyph(location, glyphname, problems, which, x, y, None, false)?)
+                scales.push(
+                    self.draw_glyph(
+                        location,
+                        glyphname,
+                        problems,
+                        which,
+                        x,
+                        y,
+                        None,
+                        false,
+                        self.reference_overlay,
+                        contour_colors
+                            .as_ref()
+                            .map(|colors| colors[which].as_slice()),
+                    )?,
+                )
             } else {
                 self.draw_emoticon(InterpolatablePlot::SHRUG, x, y)?;
             }
@@ -708,11 +804,133 @@ impl<'a> InterpolatablePlot<'a> {
             y,
             Some(scales.iter().fold(f64::INFINITY, |a, &b| a.min(b))),
             true,
+            self.reference_overlay,
+            None,
         )?;
 
+        if self.thumbnails {
+            y += self.panel_height() + InterpolatablePlot::FONT_SIZE + pad;
+            self.draw_label(
+                "all masters",
+                x,
+                y,
+                Some(InterpolatablePlot::HEAD_COLOR),
+                0.5,
+                false,
+                Some(self.panel_width()),
+                InterpolatablePlot::FONT_SIZE,
+            )?;
+            y += InterpolatablePlot::FONT_SIZE + pad;
+            self.draw_thumbnails(
+                glyphname,
+                &[problems[0].master_1_index, problems[0].master_2_index],
+                x,
+                y,
+            )?;
+        }
+
         Ok(())
     }
 
+    /// The color of each contour in the two masters of a `ContourOrder` problem
+    ///
+    /// Contours of the first master are numbered in order, and each contour of
+    /// the second master takes the number of the contour it is matched with,
+    /// so that matching contours are drawn in the same color.
+    fn contour_colors(&self, glyphname: &str, problem: &Problem) -> Option<[Vec<usize>; 2]> {
+        let glyph_id = *self.glyphname_to_id.get(glyphname)?;
+        let glyph_at =
+            |master: usize| Glyph::new_from_font(&self.font, glyph_id, self.locations.get(master)?);
+        let glyph_a = glyph_at(problem.master_1_index)?;
+        let glyph_b = glyph_at(problem.master_2_index)?;
+        let mut colors_b: Vec<usize> = (0..glyph_b.curves.len()).collect();
+        for (ix, jx) in contour_mapping(&glyph_a, &glyph_b, &self.contour_options)
+            .into_iter()
+            .enumerate()
+        {
+            if let Some(color) = colors_b.get_mut(jx) {
+                *color = ix;
+            }
+        }
+        Some([(0..glyph_a.curves.len()).collect(), colors_b])
+    }
+
+    /// Draw the glyph at every master location in rows of small thumbnails
+    ///
+    /// All thumbnails are drawn at the same scale, so that differences in
+    /// size between the masters are visible, and the masters in `highlight`
+    /// are framed.
+    fn draw_thumbnails(
+        &self,
+        glyphname: &str,
+        highlight: &[usize],
+        x: f64,
+        y: f64,
+    ) -> Result<(), Error> {
+        let Some(glyph_id) = self.glyphname_to_id.get(glyphname) else {
+            return Ok(());
+        };
+        let Some(outline) = self.font.outline_glyphs().get(*glyph_id) else {
+            return Ok(());
+        };
+        let per_row = self
+            .locations
+            .len()
+            .clamp(1, InterpolatablePlot::THUMBNAILS_PER_ROW);
+        let size = self.panel_width() / per_row as f64;
+        let inner = size - 2.0 * InterpolatablePlot::PAD;
+        let bounds = self
+            .locations
+            .iter()
+            .filter_map(|location| {
+                let loc = self.font.axes().location(location);
+                let settings = skrifa::outline::DrawSettings::unhinted(
+                    skrifa::prelude::Size::unscaled(),
+                    &loc,
+                );
+                let mut bezglyph = BezGlyph::default();
+                outline.draw(settings, &mut bezglyph).ok()?;
+                bezglyph.bounds()
+            })
+            .reduce(|a, b| a.union(b))
+            .unwrap_or(Rect::ZERO);
+        let scale = fit_scale(bounds, inner, inner, None).unwrap_or(1.0);
+
+        for (ix, location) in self.locations.iter().enumerate() {
+            let cell_x = x + (ix % per_row) as f64 * size;
+            let cell_y = y + (ix / per_row) as f64 * size;
+            let cr = cairo::Context::new(self.surface)?;
+            let (r, g, b) = if highlight.contains(&ix) {
+                InterpolatablePlot::THUMBNAIL_HIGHLIGHT_COLOR
+            } else {
+                InterpolatablePlot::BORDER_COLOR
+            };
+            cr.set_source_rgb(r, g, b);
+            cr.set_line_width(InterpolatablePlot::BORDER_WIDTH);
+            cr.rectangle(cell_x, cell_y, size, size);
+            cr.stroke()?;
+
+            cr.translate(cell_x + size / 2.0, cell_y + size / 2.0);
+            cr.scale(scale, -scale);
+            cr.translate(-bounds.center().x, -bounds.center().y);
+            let loc = self.font.axes().location(location);
+            let settings =
+                skrifa::outline::DrawSettings::unhinted(skrifa::prelude::Size::unscaled(), &loc);
+            if outline.draw(settings, &mut CairoPen::new(&cr)).is_err() {
+                continue;
+            }
+            let (r, g, b) = InterpolatablePlot::FILL_COLOR;
+            cr.set_source_rgb(r, g, b);
+            cr.fill_preserve()?;
+            let (r, g, b) = InterpolatablePlot::STROKE_COLOR;
+            cr.set_source_rgb(r, g, b);
+            cr.set_line_width(InterpolatablePlot::STROKE_WIDTH / scale);
+            cr.stroke()?;
+        }
+        Ok(())
+    }
+
+    #[allow(clippy::too_many_arguments)]
     fn draw_glyph(
         &self,
         location: &Vec<VariationSetting>,
@@ -723,8 +941,9 @@ impl<'a> InterpolatablePlot<'a> {
         y: f64,
         scale: Option<f64>,
         midway: bool,
+        reference: bool,
+        contour_colors: Option<&[usize]>,
     ) -> Result<f64, Error> {
-        let mut scale = scale;
         let glyph_id = self.glyphname_to_id.get(glyphname).unwrap();
         let outline = self.font.outline_glyphs().get(*glyph_id).unwrap();
         let loc = self.font.axes().location(location);
@@ -733,32 +952,9 @@ impl<'a> InterpolatablePlot<'a> {
             skrifa::outline::DrawSettings::unhinted(skrifa::prelude::Size::unscaled(), &loc);
         let mut bezglyph = BezGlyph::default();
         outline.draw(settings, &mut bezglyph).unwrap(); // We made one before, so we know this works.
-        let bounds = bezglyph
-            .iter()
-            .fold(None, |acc: Option<Rect>, curve| {
-                let bounds = curve.bounding_box();
-                if let Some(acc) = acc {
-                    Some(acc.union(bounds))
-                } else {
-                    Some(bounds)
-                }
-            })
-            .unwrap_or(Rect::ZERO);
-        if bounds.width() > 0.0 {
-            scale = if let Some(scale) = scale {
-                Some(scale.min(self.panel_width() / bounds.width()))
-            } else {
-                Some(self.panel_width() / bounds.width())
-            };
-        }
-        if bounds.height() > 0.0 {
-            scale = if let Some(scale) = scale {
-                Some(scale.min(self.panel_height() / bounds.height()))
-            } else {
-                Some(self.panel_height() / bounds.height())
-            };
-        }
-        let scale = scale.unwrap_or(1.0);
+        let bounds = bezglyph.bounds().unwrap_or(Rect::ZERO);
+        let scale =
+            fit_scale(bounds, self.panel_width(), self.panel_height(), scale).unwrap_or(1.0);
 
         let cr = cairo::Context::new(self.surface)?;
         cr.translate(x, y);
@@ -786,6 +982,22 @@ impl<'a> InterpolatablePlot<'a> {
         let (r, g, b) = InterpolatablePlot::FILL_COLOR;
         cr.set_source_rgb(r, g, b);
         cr.fill_preserve()?;
+        if reference && !location.is_empty() {
+            // Ghost the default master's outline between this one's fill and stroke
+            let path = cr.copy_path()?;
+            cr.new_path();
+            let default_location = self.font.axes().location(&[] as &[VariationSetting]);
+            let settings = skrifa::outline::DrawSettings::unhinted(
+                skrifa::prelude::Size::unscaled(),
+                &default_location,
+            );
+            outline.draw(settings, &mut CairoPen::new(&cr)).unwrap();
+            let (r, g, b, a) = InterpolatablePlot::REFERENCE_COLOR;
+            cr.set_source_rgba(r, g, b, a);
+            cr.set_line_width(InterpolatablePlot::STROKE_WIDTH / scale);
+            cr.stroke()?;
+            cr.append_path(&path);
+        }
         let (r, g, b) = InterpolatablePlot::STROKE_COLOR;
         cr.set_source_rgb(r, g, b);
         cr.set_line_width(InterpolatablePlot::STROKE_WIDTH / scale);
@@ -795,6 +1007,17 @@ impl<'a> InterpolatablePlot<'a> {
         // XX
         let glyph: Glyph = bezglyph.into();
 
+        if let Some(contour_colors) = contour_colors {
+            for (curve, &color) in glyph.curves.iter().zip(contour_colors) {
+                let (r, g, b, _) = InterpolatablePlot::CONTOUR_COLORS
+                    [color % InterpolatablePlot::CONTOUR_COLORS.len()];
+                trace_path(&cr, curve);
+                cr.set_source_rgba(r, g, b, InterpolatablePlot::CONTOUR_ALPHA);
+                cr.set_line_width(2.0 * InterpolatablePlot::STROKE_WIDTH / scale);
+                cr.stroke()?;
+            }
+        }
+
         for problem in problems {
             // Just for kink
             if problem.problem_type() != "Kink" {
@@ -1051,7 +1274,39 @@ impl<'a> InterpolatablePlot<'a> {
     }
 }
 
-fn lerp_location(a: &[VariationSetting], b: &[VariationSetting], t: f32) -> Vec<VariationSetting> {
+/// The largest scale at which `bounds` fits in `width` by `height`, or `scale` if that is smaller
+fn fit_scale(bounds: Rect, width: f64, height: f64, scale: Option<f64>) -> Option<f64> {
+    let mut scale = scale;
+    if bounds.width() > 0.0 {
+        let fit = width / bounds.width();
+        scale = Some(scale.map_or(fit, |scale| scale.min(fit)));
+    }
+    if bounds.height() > 0.0 {
+        let fit = height / bounds.height();
+        scale = Some(scale.map_or(fit, |scale| scale.min(fit)));
+    }
+    scale
+}
+
+/// Add a contour to the current path of `cr`
+fn trace_path(cr: &Context, path: &BezPath) {
+    for (ix, segment) in path.segments().enumerate() {
+        let cubic = segment.to_cubic();
+        if ix == 0 {
+            cr.move_to(cubic.p0.x, cubic.p0.y);
+        }
+        cr.curve_to(
+            cubic.p1.x, cubic.p1.y, cubic.p2.x, cubic.p2.y, cubic.p3.x, cubic.p3.y,
+        );
+    }
+    cr.close_path();
+}
+
+pub(crate) fn lerp_location(
+    a: &[VariationSetting],
+    b: &[VariationSetting],
+    t: f32,
+) -> Vec<VariationSetting> {
     a.iter()
         .zip(b.iter())
         .map(|(a, b)| {
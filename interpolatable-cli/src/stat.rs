@@ -0,0 +1,190 @@
+use indexmap::IndexMap;
+use interpolatable::Problem;
+use read_fonts::{tables::stat::AxisValue, TableProvider};
+use serde::Serialize;
+use skrifa::{setting::VariationSetting, FontRef, MetadataProvider, Tag};
+
+use crate::markdown::markdown_report;
+
+/// The label for problems between masters which don't differ along any axis
+const NO_AXIS: &str = "Other";
+
+/// A style axis from the STAT table
+struct StyleAxis {
+    tag: Tag,
+    name: String,
+    ordering: u16,
+    /// The axis's default value, from the fvar table
+    default: f32,
+    /// The named values of the axis (such as 700 for "Bold")
+    values: Vec<(f32, String)>,
+}
+
+/// The style axes declared in the font's STAT table, used to organize a
+/// report by the axes the font itself declares rather than by raw locations
+pub(crate) struct StyleAxes {
+    axes: Vec<StyleAxis>,
+}
+
+/// The problems found along each style axis, per glyph
+#[derive(Serialize, Default)]
+pub(crate) struct AxisReport(pub IndexMap<String, IndexMap<String, Vec<Problem>>>);
+
+impl StyleAxes {
+    /// Read the STAT table, returning `None` if the font doesn't have one
+    pub(crate) fn new(font: &FontRef) -> Option<Self> {
+        let stat = font.stat().ok()?;
+        let name = |id| {
+            font.localized_strings(id)
+                .english_or_first()
+                .map(|s| s.to_string())
+        };
+        let fvar_axes = font.axes();
+        let mut axes: Vec<StyleAxis> = stat
+            .design_axes()
+            .ok()?
+            .iter()
+            .map(|record| {
+                let tag = record.axis_tag();
+                StyleAxis {
+                    tag,
+                    name: name(record.axis_name_id()).unwrap_or_else(|| tag.to_string()),
+                    ordering: record.axis_ordering(),
+                    default: fvar_axes
+                        .iter()
+                        .find(|axis| axis.tag() == tag)
+                        .map_or(0.0, |axis| axis.default_value()),
+                    values: vec![],
+                }
+            })
+            .collect();
+        if let Some(Ok(axis_values)) = stat.offset_to_axis_values() {
+            for value in axis_values.axis_values().iter().flatten() {
+                let (axis_index, value, name_id) = match value {
+                    AxisValue::Format1(v) => (v.axis_index(), v.value(), v.value_name_id()),
+                    AxisValue::Format2(v) => (v.axis_index(), v.nominal_value(), v.value_name_id()),
+                    AxisValue::Format3(v) => (v.axis_index(), v.value(), v.value_name_id()),
+                    // Combinations of axis values don't name any one axis
+                    AxisValue::Format4(_) => continue,
+                };
+                if let (Some(axis), Some(name)) = (axes.get_mut(axis_index as usize), name(name_id))
+                {
+                    axis.values.push((value.to_f64() as f32, name));
+                }
+            }
+        }
+        axes.sort_by_key(|axis| axis.ordering);
+        Some(StyleAxes { axes })
+    }
+
+    fn value_at(axis: &StyleAxis, location: &[VariationSetting]) -> f32 {
+        location
+            .iter()
+            .find(|setting| setting.selector == axis.tag)
+            .map_or(axis.default, |setting| setting.value)
+    }
+
+    /// Describe a value along an axis by its STAT name, if it has one
+    fn describe_value(axis: &StyleAxis, value: f32) -> String {
+        axis.values
+            .iter()
+            .find(|(v, _)| (v - value).abs() < f32::EPSILON)
+            .map_or_else(|| value.to_string(), |(_, name)| name.clone())
+    }
+
+    /// The axes along which two locations differ, in STAT order
+    ///
+    /// Returns the indices of the axes, for sorting, and a description of
+    /// them with the value at each end, by its STAT name where the font gives
+    /// one: for example `Weight (Regular → Bold)`.
+    fn span(
+        &self,
+        location_1: &[VariationSetting],
+        location_2: &[VariationSetting],
+    ) -> (Vec<usize>, String) {
+        let (indices, spans): (Vec<usize>, Vec<String>) = self
+            .axes
+            .iter()
+            .enumerate()
+            .filter_map(|(ix, axis)| {
+                let value_1 = Self::value_at(axis, location_1);
+                let value_2 = Self::value_at(axis, location_2);
+                (value_1 != value_2).then(|| {
+                    (
+                        ix,
+                        format!(
+                            "{} ({} → {})",
+                            axis.name,
+                            Self::describe_value(axis, value_1),
+                            Self::describe_value(axis, value_2)
+                        ),
+                    )
+                })
+            })
+            .unzip();
+        if spans.is_empty() {
+            // Sort after the real axes
+            (vec![usize::MAX], NO_AXIS.to_string())
+        } else {
+            (indices, spans.join(", "))
+        }
+    }
+
+    /// Group a report's problems by the axes along which their masters differ
+    ///
+    /// Groups are sorted by the STAT axis ordering, so that (for example) all
+    /// the weight problems come before the width problems.
+    pub(crate) fn group(
+        &self,
+        report: &IndexMap<String, Vec<Problem>>,
+        locations: &[Vec<VariationSetting>],
+    ) -> AxisReport {
+        let mut grouped = AxisReport::default();
+        let mut order: IndexMap<String, Vec<usize>> = IndexMap::new();
+        for (glyphname, problems) in report {
+            for problem in problems {
+                let (key, span) = match (
+                    locations.get(problem.master_1_index),
+                    locations.get(problem.master_2_index),
+                ) {
+                    (Some(location_1), Some(location_2)) => self.span(location_1, location_2),
+                    _ => (vec![usize::MAX], NO_AXIS.to_string()),
+                };
+                order.insert(span.clone(), key);
+                grouped
+                    .0
+                    .entry(span)
+                    .or_default()
+                    .entry(glyphname.clone())
+                    .or_default()
+                    .push(problem.clone());
+            }
+        }
+        grouped
+            .0
+            .sort_by(|a, _, b, _| order[a].cmp(&order[b]).then(a.cmp(b)));
+        grouped
+    }
+}
+
+impl AxisReport {
+    pub(crate) fn print(&self) {
+        for (span, glyphs) in self.0.iter() {
+            println!("{}:", span);
+            for (glyphname, problems) in glyphs {
+                println!("  Problems with glyph {}:", glyphname);
+                for problem in problems {
+                    println!("    {:#?}", problem);
+                }
+            }
+        }
+    }
+
+    pub(crate) fn markdown(&self) -> String {
+        self.0
+            .iter()
+            .map(|(span, glyphs)| format!("## {}\n\n{}", span, markdown_report(glyphs)))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
@@ -0,0 +1,189 @@
+use std::{collections::HashMap, fmt::Write, path::Path};
+
+use indexmap::IndexMap;
+use interpolatable::{BezGlyph, Glyph, Problem};
+use itertools::Itertools;
+use kurbo::Rect;
+use skrifa::{setting::VariationSetting, FontRef, GlyphId, MetadataProvider};
+
+use crate::{plot::lerp_location, units_per_em};
+
+const FILL_COLOR: &str = "#ccc";
+const STROKE_COLOR: &str = "#1a1a1a";
+const LABEL_COLOR: &str = "#333";
+const PROBLEM_COLOR: &str = "#f0f";
+const PROBLEM_CONTOUR_COLOR: &str = "#f00";
+
+/// Writes one SVG file per problematic glyph, showing each pair of masters
+/// with a problem alongside their midway interpolation
+pub(crate) struct SvgWriter<'a> {
+    font: &'a FontRef<'a>,
+    locations: &'a [Vec<VariationSetting>],
+    glyphname_to_id: &'a HashMap<String, GlyphId>,
+    /// Size of labels and annotations, in font units
+    label_size: f64,
+}
+
+impl<'a> SvgWriter<'a> {
+    pub fn new(
+        font: &'a FontRef<'a>,
+        locations: &'a [Vec<VariationSetting>],
+        glyphname_to_id: &'a HashMap<String, GlyphId>,
+    ) -> Self {
+        let upem = units_per_em(font);
+        SvgWriter {
+            font,
+            locations,
+            glyphname_to_id,
+            label_size: upem as f64 / 20.0,
+        }
+    }
+
+    /// Write `<glyphname>.svg` into `dir` for each glyph in the report
+    pub fn write_all(
+        &self,
+        dir: &Path,
+        problems: &IndexMap<String, Vec<Problem>>,
+    ) -> std::io::Result<()> {
+        std::fs::create_dir_all(dir)?;
+        for (glyphname, problems) in problems {
+            if let Some(svg) = self.glyph_svg(glyphname, problems) {
+                let filename = format!("{}.svg", glyphname.replace(['/', '\\'], "_"));
+                std::fs::write(dir.join(filename), svg)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn outline(&self, gid: GlyphId, location: &[VariationSetting]) -> Option<Glyph> {
+        let outline = self.font.outline_glyphs().get(gid)?;
+        let loc = self.font.axes().location(location);
+        let settings =
+            skrifa::outline::DrawSettings::unhinted(skrifa::prelude::Size::unscaled(), &loc);
+        let mut bezglyph = BezGlyph::default();
+        outline.draw(settings, &mut bezglyph).ok()?;
+        Some(bezglyph.into())
+    }
+
+    fn glyph_svg(&self, glyphname: &str, problems: &[Problem]) -> Option<String> {
+        let gid = *self.glyphname_to_id.get(glyphname)?;
+        let pad = self.label_size;
+        let mut body = String::new();
+        let mut width: f64 = 0.0;
+        let mut y = pad;
+        // Problems for the same pair of masters are drawn together on one row
+        for (_, group) in &problems
+            .iter()
+            .chunk_by(|p| (p.master_1_index, p.master_2_index))
+        {
+            let group = group.collect::<Vec<_>>();
+            let loc_1 = self.locations.get(group[0].master_1_index)?;
+            let loc_2 = self.locations.get(group[0].master_2_index)?;
+            let midway = lerp_location(loc_1, loc_2, 0.5);
+            let panels = [
+                (group[0].master_1_name.as_str(), self.outline(gid, loc_1)?),
+                ("midway interpolation", self.outline(gid, &midway)?),
+                (group[0].master_2_name.as_str(), self.outline(gid, loc_2)?),
+            ];
+            let bounds = panels
+                .iter()
+                .filter_map(|(_, glyph)| glyph.bounds())
+                .reduce(|a, b| a.union(b))
+                .unwrap_or(Rect::ZERO);
+
+            let mut x = pad;
+            for (name, glyph) in panels.iter() {
+                let _ = writeln!(
+                    body,
+                    r#"<text x="{}" y="{}" font-size="{}" fill="{}">{}</text>"#,
+                    x,
+                    y + self.label_size,
+                    self.label_size,
+                    LABEL_COLOR,
+                    escape(name)
+                );
+                let _ = writeln!(
+                    body,
+                    r#"<g transform="translate({} {}) scale(1 -1)">"#,
+                    x - bounds.min_x(),
+                    y + self.label_size + pad + bounds.max_y()
+                );
+                self.draw_glyph(&mut body, glyph, &group);
+                body.push_str("</g>\n");
+                x += bounds.width() + pad;
+            }
+            width = width.max(x);
+            y += self.label_size + pad + bounds.height() + pad;
+            let problem_types = group.iter().map(|p| p.problem_type()).unique().join(", ");
+            let _ = writeln!(
+                body,
+                r#"<text x="{}" y="{}" font-size="{}" fill="{}">{}</text>"#,
+                pad,
+                y + self.label_size,
+                self.label_size,
+                LABEL_COLOR,
+                escape(&problem_types)
+            );
+            y += self.label_size + pad * 2.0;
+        }
+        Some(format!(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 {} {}" width="{}" height="{}">
+<title>{}</title>
+{}</svg>
+"#,
+            width,
+            y,
+            width,
+            y,
+            escape(glyphname),
+            body
+        ))
+    }
+
+    fn draw_glyph(&self, body: &mut String, glyph: &Glyph, problems: &[&Problem]) {
+        let path = glyph.curves.iter().map(|c| c.to_svg()).join(" ");
+        let _ = writeln!(
+            body,
+            r#"<path d="{}" fill="{}" stroke="{}" stroke-width="{}"/>"#,
+            path,
+            FILL_COLOR,
+            STROKE_COLOR,
+            self.label_size / 10.0
+        );
+        for problem in problems {
+            match (problem.contour, problem.node) {
+                (Some(contour), Some(node)) => {
+                    if let Some(pt) = glyph.points.get(contour).and_then(|c| c.get(node)) {
+                        let _ = writeln!(
+                            body,
+                            r#"<circle cx="{}" cy="{}" r="{}" fill="none" stroke="{}" stroke-width="{}"/>"#,
+                            pt.point.x,
+                            pt.point.y,
+                            self.label_size / 2.0,
+                            PROBLEM_COLOR,
+                            self.label_size / 10.0
+                        );
+                    }
+                }
+                (Some(contour), None) => {
+                    if let Some(curve) = glyph.curves.get(contour) {
+                        let _ = writeln!(
+                            body,
+                            r#"<path d="{}" fill="none" stroke="{}" stroke-width="{}"/>"#,
+                            curve.to_svg(),
+                            PROBLEM_CONTOUR_COLOR,
+                            self.label_size / 5.0
+                        );
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
@@ -0,0 +1,45 @@
+use std::path::Path;
+
+use indexmap::IndexMap;
+use serde::Deserialize;
+
+use crate::patterns::glob_match;
+
+/// Tolerances which replace the defaults for some glyphs
+#[derive(Deserialize, Default, Clone, Copy, Debug, PartialEq)]
+pub(crate) struct GlyphTolerances {
+    pub tolerance: Option<f64>,
+    pub kinkiness: Option<f64>,
+}
+
+/// Per-glyph tolerances, read from a `--tolerance-config` file
+///
+/// The file is a JSON object mapping glyph name patterns (which may use `*`
+/// and `?` wildcards) to the tolerances to use for matching glyphs, for
+/// example `{"*.swsh": {"tolerance": 0.8}, "period": {"kinkiness": 0.2}}`.
+pub(crate) struct ToleranceConfig(IndexMap<String, GlyphTolerances>);
+
+impl ToleranceConfig {
+    pub(crate) fn load(path: &Path) -> Result<Self, String> {
+        let contents = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+        serde_json::from_str(&contents)
+            .map(ToleranceConfig)
+            .map_err(|e| e.to_string())
+    }
+
+    /// The tolerances for a glyph
+    ///
+    /// If several patterns match the glyph, each tolerance is taken from the
+    /// first pattern in the file which sets it.
+    pub(crate) fn for_glyph(&self, glyphname: &str) -> GlyphTolerances {
+        self.0
+            .iter()
+            .filter(|(pattern, _)| glob_match(pattern, glyphname))
+            .fold(GlyphTolerances::default(), |found, (_, tolerances)| {
+                GlyphTolerances {
+                    tolerance: found.tolerance.or(tolerances.tolerance),
+                    kinkiness: found.kinkiness.or(tolerances.kinkiness),
+                }
+            })
+    }
+}
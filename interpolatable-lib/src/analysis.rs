@@ -0,0 +1,113 @@
+use crate::{
+    basiccompat::test_compatibility,
+    calibrate::Tolerances,
+    contour_mapping,
+    kink::{kink_candidates, KinkCandidate, DEFAULT_SMOOTHNESS, DEFAULT_UPEM},
+    problems::{Badness, Problem, ProblemDetails},
+    run_tests_with,
+    translation::{contour_movement, translation_offset, DEFAULT_MIN_CONTOUR_MOVEMENT},
+    Glyph, RunTestsOptions,
+};
+
+/// When a finding is reported as a problem
+#[derive(Debug, Clone)]
+enum Threshold {
+    /// At any tolerance
+    Always,
+    /// When the tolerance is above this match ratio
+    Ratio(f64),
+    /// Depending on both the tolerance and the kinkiness
+    Kink(KinkCandidate),
+}
+
+/// Everything [crate::run_tests] could report about a pair of glyphs, at any tolerance
+///
+/// Created by [analyze]. The geometric work is done once, up front; the
+/// problems at particular tolerances can then be picked out cheaply with
+/// [Analysis::problems_at], for example while a user drags a slider.
+#[derive(Debug, Clone)]
+pub struct Analysis {
+    findings: Vec<(Problem, Threshold)>,
+    upem: u16,
+}
+
+impl Analysis {
+    /// The problems which [crate::run_tests] would report with these tolerances
+    ///
+    /// The problems are the same as those from [crate::run_tests] with the given
+    /// `tolerance` and `kinkiness` and its other defaults, but kinks are
+    /// listed after the other problems rather than with their contours.
+    pub fn problems_at(&self, tolerances: &Tolerances) -> Vec<Problem> {
+        self.findings
+            .iter()
+            .filter_map(|(problem, threshold)| match threshold {
+                Threshold::Always => Some(problem.clone()),
+                Threshold::Ratio(ratio) => (*ratio < tolerances.tolerance).then(|| problem.clone()),
+                Threshold::Kink(candidate) => {
+                    let this_tolerance = candidate.tolerance_at(
+                        tolerances.tolerance,
+                        tolerances.kinkiness,
+                        DEFAULT_SMOOTHNESS,
+                        self.upem,
+                    )?;
+                    let mut problem = problem.clone();
+                    problem.badness = Some(Badness::from_ratio(this_tolerance));
+                    Some(problem)
+                }
+            })
+            .collect()
+    }
+}
+
+/// Find everything which could be wrong with a pair of glyphs, at any tolerance
+///
+/// This runs the checks of [crate::run_tests] (with its defaults, apart from the
+/// tolerances) once, keeping each finding together with the tolerances at
+/// which it would be reported, so that [Analysis::problems_at] can produce the
+/// problems for any tolerances without repeating the work.
+pub fn analyze(glyph_a: &Glyph, glyph_b: &Glyph, upem: Option<u16>) -> Analysis {
+    let upem = upem.unwrap_or(DEFAULT_UPEM);
+    // At a tolerance of 1.0 every imperfect match is reported, and the badness
+    // of each problem records the tolerance below which it would disappear
+    let mut findings: Vec<(Problem, Threshold)> = run_tests_with(
+        glyph_a,
+        glyph_b,
+        &RunTestsOptions::default()
+            .with_tolerance(1.0)
+            .with_upem(upem),
+    )
+    .into_iter()
+    .filter(|problem| !matches!(problem.details, ProblemDetails::Kink { .. }))
+    .map(|problem| {
+        let threshold = match problem.badness {
+            Some(badness) => Threshold::Ratio(badness.ratio()),
+            None => Threshold::Always,
+        };
+        (problem, threshold)
+    })
+    .collect();
+
+    // Kinks depend on the kinkiness as well, so they are found separately
+    if test_compatibility(glyph_a, glyph_b).is_empty() {
+        let mapping = contour_mapping(glyph_a, glyph_b, &RunTestsOptions::default());
+        for (ix, &jx) in mapping.iter().enumerate() {
+            let (Some(contour0), Some(contour1)) = (glyph_a.points.get(ix), glyph_b.points.get(jx))
+            else {
+                continue;
+            };
+            if translation_offset(contour0, contour1).is_some()
+                || contour_movement(contour0, contour1) < DEFAULT_MIN_CONTOUR_MOVEMENT
+            {
+                continue;
+            }
+            for candidate in kink_candidates(contour0, contour1, DEFAULT_SMOOTHNESS) {
+                findings.push((
+                    // The badness depends on the kinkiness, so is set later
+                    Problem::kink(glyph_a, glyph_b, ix, candidate.node, 1.0),
+                    Threshold::Kink(candidate),
+                ));
+            }
+        }
+    }
+    Analysis { findings, upem }
+}
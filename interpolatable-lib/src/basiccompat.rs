@@ -1,6 +1,30 @@
 use crate::{problems::Problem, Glyph};
 
-pub(crate) fn test_compatibility<'a>(glyph1: &'a Glyph, glyph2: &'a Glyph) -> Vec<Problem> {
+/// Contours whose sizes are closer than this ratio are considered to have similar area
+const SIMILAR_AREA_RATIO: f64 = 0.9;
+/// A contour with less than this fraction of the other's point density is considered sparse
+const SPARSE_DENSITY_RATIO: f64 = 0.75;
+
+/// Does a node count mismatch look like one master had its overlaps removed?
+///
+/// Overlap removal tends to leave a contour with roughly the same area but
+/// far fewer points along its outline than its counterpart.
+fn likely_overlap_removal(size_1: f64, size_2: f64, density_1: f64, density_2: f64) -> bool {
+    let (size_1, size_2) = (size_1.abs(), size_2.abs());
+    if size_1 == 0.0 || size_2 == 0.0 || density_1 == 0.0 || density_2 == 0.0 {
+        return false;
+    }
+    size_1.min(size_2) / size_1.max(size_2) >= SIMILAR_AREA_RATIO
+        && density_1.min(density_2) / density_1.max(density_2) <= SPARSE_DENSITY_RATIO
+}
+
+/// Test that two glyphs are structurally compatible
+///
+/// This only checks that the glyphs have the same number of contours, the same
+/// number of nodes in each contour, and the same kinds of nodes. It is much
+/// cheaper than [crate::run_tests], which runs it first and stops if it finds
+/// any problems.
+pub fn test_compatibility<'a>(glyph1: &'a Glyph, glyph2: &'a Glyph) -> Vec<Problem> {
     let mut problems = vec![];
     if glyph1.curves.len() != glyph2.curves.len() {
         problems.push(Problem::path_count(
@@ -12,13 +36,32 @@ pub(crate) fn test_compatibility<'a>(glyph1: &'a Glyph, glyph2: &'a Glyph) -> Ve
     }
     for (path_index, (p1, p2)) in glyph1.points.iter().zip(glyph2.points.iter()).enumerate() {
         if p1.len() != p2.len() {
-            problems.push(Problem::node_count(
-                glyph1,
-                glyph2,
-                path_index,
-                p1.len(),
-                p2.len(),
-            ));
+            let density_1 = glyph1.contour_point_density(path_index).unwrap_or(0.0);
+            let density_2 = glyph2.contour_point_density(path_index).unwrap_or(0.0);
+            if likely_overlap_removal(
+                glyph1.green_vectors[path_index][0],
+                glyph2.green_vectors[path_index][0],
+                density_1,
+                density_2,
+            ) {
+                problems.push(Problem::likely_overlap_removal(
+                    glyph1,
+                    glyph2,
+                    path_index,
+                    p1.len(),
+                    p2.len(),
+                    density_1,
+                    density_2,
+                ));
+            } else {
+                problems.push(Problem::node_count(
+                    glyph1,
+                    glyph2,
+                    path_index,
+                    p1.len(),
+                    p2.len(),
+                ));
+            }
         }
         for (node_index, (point1, point2)) in p1.iter().zip(p2.iter()).enumerate() {
             if point1.is_control != point2.is_control {
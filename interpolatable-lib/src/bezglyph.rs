@@ -1,28 +1,102 @@
-use kurbo::BezPath;
+use kurbo::{BezPath, Point, Rect};
 
 #[derive(Default, Debug)]
-pub struct BezGlyph(pub(crate) Vec<BezPath>);
+pub struct BezGlyph {
+    pub(crate) paths: Vec<BezPath>,
+    /// If set, coordinates are rounded to multiples of this when converting to a [crate::Glyph]
+    pub(crate) grid: Option<f64>,
+}
 
 impl BezGlyph {
     pub fn new_from_paths(b: Vec<BezPath>) -> Self {
-        BezGlyph(b)
+        BezGlyph {
+            paths: b,
+            grid: None,
+        }
     }
     #[allow(clippy::should_implement_trait)]
     pub fn next(&mut self) -> &mut BezPath {
-        self.0.push(BezPath::new());
+        self.paths.push(BezPath::new());
         #[allow(clippy::unwrap_used)] // We just added it
-        self.0.last_mut().unwrap()
+        self.paths.last_mut().unwrap()
     }
     pub fn current(&mut self) -> &mut BezPath {
-        if self.0.is_empty() {
-            self.0.push(BezPath::new());
+        if self.paths.is_empty() {
+            self.paths.push(BezPath::new());
         }
         #[allow(clippy::unwrap_used)] // We know it's not empty
-        self.0.last_mut().unwrap()
+        self.paths.last_mut().unwrap()
+    }
+
+    /// Add a TrueType-style quadratic spline to the current contour
+    ///
+    /// `points` are any number of off-curve points followed by an on-curve
+    /// point, as in a fontTools `qCurveTo`. The on-curve points implied
+    /// midway between consecutive off-curve points are reconstructed, so a
+    /// spline drawn with implied on-curve points produces exactly the same
+    /// path (and so the same node count) as one drawn with them explicit.
+    pub fn qcurve_to<P: Into<Point> + Copy>(&mut self, points: &[P]) {
+        let points: Vec<Point> = points.iter().map(|&p| p.into()).collect();
+        let Some((&on_curve, off_curves)) = points.split_last() else {
+            return;
+        };
+        let path = self.current();
+        if off_curves.is_empty() {
+            path.line_to(on_curve);
+            return;
+        }
+        for pair in off_curves.windows(2) {
+            path.quad_to(pair[0], pair[0].midpoint(pair[1]));
+        }
+        if let Some(&last) = off_curves.last() {
+            path.quad_to(last, on_curve);
+        }
     }
 
     pub fn iter(&self) -> impl Iterator<Item = &BezPath> {
-        self.0.iter()
+        self.paths.iter()
+    }
+
+    /// The bounding box of all the paths, or `None` if there are none
+    pub fn bounds(&self) -> Option<Rect> {
+        crate::union_bounds(self.paths.iter())
+    }
+
+    /// Round all coordinates to multiples of `grid` (e.g. 1.0 for integer
+    /// font units) when this is converted into a [crate::Glyph]
+    ///
+    /// The statistics are computed from the rounded coordinates, so that the
+    /// tests see what a font with integer coordinates will actually do,
+    /// rather than sub-unit noise in the source outlines.
+    pub fn set_grid(&mut self, grid: Option<f64>) {
+        self.grid = grid.filter(|&g| g > 0.0);
+    }
+
+    /// The paths, with their coordinates rounded to the grid if one is set
+    pub(crate) fn into_rounded_paths(self) -> Vec<BezPath> {
+        let Some(grid) = self.grid else {
+            return self.paths;
+        };
+        let round = |p: Point| Point::new((p.x / grid).round() * grid, (p.y / grid).round() * grid);
+        self.paths
+            .into_iter()
+            .map(|path| {
+                path.elements()
+                    .iter()
+                    .map(|el| match *el {
+                        kurbo::PathEl::MoveTo(p) => kurbo::PathEl::MoveTo(round(p)),
+                        kurbo::PathEl::LineTo(p) => kurbo::PathEl::LineTo(round(p)),
+                        kurbo::PathEl::QuadTo(p0, p1) => {
+                            kurbo::PathEl::QuadTo(round(p0), round(p1))
+                        }
+                        kurbo::PathEl::CurveTo(p0, p1, p2) => {
+                            kurbo::PathEl::CurveTo(round(p0), round(p1), round(p2))
+                        }
+                        kurbo::PathEl::ClosePath => kurbo::PathEl::ClosePath,
+                    })
+                    .collect()
+            })
+            .collect()
     }
 }
 
@@ -0,0 +1,63 @@
+use serde::Serialize;
+
+use crate::{
+    kink::DEFAULT_KINKINESS, problems::ProblemDetails, run_tests_with, Glyph, RunTestsOptions,
+};
+
+/// How far below the worst observed match ratio the suggested tolerance is set
+const CALIBRATION_SLACK: f64 = 0.01;
+
+/// Thresholds suggested by [calibrate]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Tolerances {
+    /// The `tolerance` to pass to [crate::run_tests]
+    pub tolerance: f64,
+    /// The `kinkiness` to pass to [crate::run_tests]
+    pub kinkiness: f64,
+}
+
+/// Suggest tolerances from pairs of glyphs which are known to interpolate well
+///
+/// The checks are run on each pair at their most sensitive, and the worst
+/// match ratio seen is used to set a `tolerance` just below it, so that none
+/// of these pairs would be reported. Kinks are calibrated separately, through
+/// the `kinkiness`, so that a single slightly kinky glyph doesn't loosen every
+/// other check. Problems which are reported at any tolerance (such as
+/// incompatible node counts) can't be calibrated away and are ignored; if the
+/// pairs produce none of the other problems, the defaults are returned (with
+/// the tolerance just below a perfect match).
+pub fn calibrate(glyph_pairs: &[(Glyph, Glyph)]) -> Tolerances {
+    let mut worst_ratio = 1.0f64;
+    let mut worst_kink_ratio: Option<f64> = None;
+    for (glyph_a, glyph_b) in glyph_pairs {
+        let problems = run_tests_with(
+            glyph_a,
+            glyph_b,
+            &RunTestsOptions::default().with_tolerance(1.0),
+        );
+        for problem in problems {
+            let Some(badness) = problem.badness else {
+                continue;
+            };
+            if matches!(problem.details, ProblemDetails::Kink { .. }) {
+                worst_kink_ratio =
+                    Some(worst_kink_ratio.map_or(badness.ratio(), |r| r.min(badness.ratio())));
+            } else {
+                worst_ratio = worst_ratio.min(badness.ratio());
+            }
+        }
+    }
+    let tolerance = (worst_ratio - CALIBRATION_SLACK).clamp(0.0, 1.0);
+    // A kink with ratio `r` at the default kinkiness is reported when
+    // `tolerance * kinkiness / DEFAULT_KINKINESS > r`
+    let kinkiness = match worst_kink_ratio {
+        Some(ratio) if tolerance > 0.0 => {
+            DEFAULT_KINKINESS * ratio / tolerance * (1.0 - CALIBRATION_SLACK)
+        }
+        _ => DEFAULT_KINKINESS,
+    };
+    Tolerances {
+        tolerance,
+        kinkiness,
+    }
+}
@@ -0,0 +1,90 @@
+use crate::{problems::Problem, Glyph};
+
+/// Centroid movements smaller than this (in font units) are treated as noise
+const CENTROID_EPSILON: f64 = 0.5;
+/// A contour moving this many times further than the average of the other contours is suspicious
+const JUMP_FACTOR: f64 = 4.0;
+/// ...as long as it moves at least this far, in font units
+const JUMP_MINIMUM: f64 = 10.0;
+
+fn centroid(glyph: &Glyph, contour: usize) -> (f64, f64) {
+    let vector = &glyph.green_vectors[contour];
+    (vector[1], vector[2])
+}
+
+fn distance(a: (f64, f64), b: (f64, f64)) -> f64 {
+    (b.0 - a.0).hypot(b.1 - a.1)
+}
+
+/// Test that the center of mass of each contour moves smoothly across masters
+///
+/// The glyphs must be given in order along a single axis. Two kinds of
+/// movement are reported as `CentroidJump` problems:
+///
+/// * A contour whose centroid moves one way and then back again
+///   (non-monotonically in x or y) across three consecutive masters
+/// * A contour whose centroid moves much further between two consecutive
+///   masters than the other contours in the glyph do
+///
+/// Either usually indicates a point-matching or start point problem.
+pub fn test_centroid_drift(glyphs: &[&Glyph]) -> Vec<Problem> {
+    let mut problems = vec![];
+    let Some(first) = glyphs.first() else {
+        return problems;
+    };
+    let contours = first.green_vectors.len();
+    if glyphs.iter().any(|g| g.green_vectors.len() != contours) {
+        return problems;
+    }
+
+    for triple in glyphs.windows(3) {
+        if let [before, middle, after] = triple {
+            for ix in 0..contours {
+                let (c0, c1, c2) = (
+                    centroid(before, ix),
+                    centroid(middle, ix),
+                    centroid(after, ix),
+                );
+                let reverses = |a: f64, b: f64, c: f64| {
+                    let (d0, d1) = (b - a, c - b);
+                    d0.abs() > CENTROID_EPSILON
+                        && d1.abs() > CENTROID_EPSILON
+                        && d0.signum() != d1.signum()
+                };
+                if reverses(c0.0, c1.0, c2.0) || reverses(c0.1, c1.1, c2.1) {
+                    problems.push(Problem::centroid_jump(
+                        middle,
+                        after,
+                        ix,
+                        "non-monotonic",
+                        distance(c1, c2),
+                    ));
+                }
+            }
+        }
+    }
+
+    if contours > 1 {
+        for pair in glyphs.windows(2) {
+            if let [before, after] = pair {
+                let distances: Vec<f64> = (0..contours)
+                    .map(|ix| distance(centroid(before, ix), centroid(after, ix)))
+                    .collect();
+                let total: f64 = distances.iter().sum();
+                for (ix, &this_distance) in distances.iter().enumerate() {
+                    let others_mean = (total - this_distance) / (contours - 1) as f64;
+                    if this_distance > JUMP_MINIMUM && this_distance > JUMP_FACTOR * others_mean {
+                        problems.push(Problem::centroid_jump(
+                            before,
+                            after,
+                            ix,
+                            "disproportionate",
+                            this_distance,
+                        ));
+                    }
+                }
+            }
+        }
+    }
+    problems
+}
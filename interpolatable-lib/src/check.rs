@@ -0,0 +1,25 @@
+use crate::{utils::Matching, Glyph, Problem};
+
+/// The parameters a [Check] is being run with
+pub struct CheckContext<'a> {
+    /// The tolerance for problems, after applying the default
+    pub tolerance: f64,
+    /// The kinkiness requested by the caller, if any
+    pub kinkiness: Option<f64>,
+    /// The UPEM value requested by the caller, if any
+    pub upem: Option<u16>,
+    /// The contour matching found by the contour order check, if the
+    /// contours of `glyph_b` needed reordering to match those of `glyph_a`
+    pub matching: Option<&'a Matching>,
+}
+
+/// A custom check which can be run alongside the built-in checks
+///
+/// Implement this trait to add your own rules (for example, foundry-specific
+/// checks) and pass them to [crate::run_tests]. Custom checks are run after
+/// the built-in checks, once the glyphs have passed the basic compatibility
+/// tests (same number of contours and nodes). Use [Problem::custom] to report
+/// problems.
+pub trait Check {
+    fn run(&self, glyph_a: &Glyph, glyph_b: &Glyph, ctx: &CheckContext) -> Vec<Problem>;
+}
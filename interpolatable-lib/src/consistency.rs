@@ -0,0 +1,116 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::{contour_mapping, problems::Problem, Glyph, RunTestsOptions};
+
+/// Contours whose shape descriptors differ by less than this proportion of
+/// their size are considered to be the same shape
+const SAME_SHAPE_TOLERANCE: f64 = 0.02;
+
+/// A glyph pair and the problems found in it, as input to [test_shared_shapes]
+pub struct GlyphPairReport<'a> {
+    /// The name of the glyph
+    pub name: &'a str,
+    /// The glyph at one master
+    pub glyph_a: &'a Glyph,
+    /// The glyph at another master
+    pub glyph_b: &'a Glyph,
+    /// The problems found in the glyph, from [crate::run_tests] or elsewhere
+    pub problems: &'a [Problem],
+}
+
+/// The parts of a contour's feature vector which describe its shape, but not
+/// its position, in both masters
+fn shape_descriptor(glyph_a: &Glyph, glyph_b: &Glyph, ix: usize, jx: usize) -> Option<Vec<f64>> {
+    let (a, b) = (
+        glyph_a.green_vectors.get(ix)?,
+        glyph_b.green_vectors.get(jx)?,
+    );
+    Some(vec![a[0], a[3], a[4], a[5], b[0], b[3], b[4], b[5]])
+}
+
+/// Check that contours with the same shape in different glyphs interpolate the same way
+///
+/// The same sub-shape (such as the bowls of b, d, p and q) often appears in
+/// several glyphs, and should behave the same in each of them. This finds
+/// contours in different glyphs whose size, spread and slant are nearly
+/// identical in both masters, and compares the contour-level problems
+/// reported for them. When a contour has a problem which a same-shaped
+/// contour elsewhere does not (the bowl of d has a kink, but the bowl of b
+/// is fine), a `SharedShapeInconsistency` problem is returned for it, naming
+/// the contour which behaves differently. Each is paired with the name of
+/// the glyph it was found in.
+///
+/// Contours are matched between masters as in [crate::run_tests].
+pub fn test_shared_shapes(glyphs: &[GlyphPairReport]) -> Vec<(String, Problem)> {
+    // (glyph index, contour index, descriptor)
+    let mut contours = vec![];
+    for (gx, report) in glyphs.iter().enumerate() {
+        let mapping = contour_mapping(report.glyph_a, report.glyph_b, &RunTestsOptions::default());
+        for (ix, &jx) in mapping.iter().enumerate() {
+            if let Some(descriptor) = shape_descriptor(report.glyph_a, report.glyph_b, ix, jx) {
+                contours.push((gx, ix, descriptor));
+            }
+        }
+    }
+    // Sorting by size means we only need to compare nearby contours
+    contours.sort_by(|a, b| a.2[0].abs().total_cmp(&b.2[0].abs()));
+
+    let problem_types: HashMap<(usize, usize), HashSet<String>> = contours
+        .iter()
+        .map(|&(gx, ix, _)| {
+            let types = glyphs[gx]
+                .problems
+                .iter()
+                .filter(|p| p.contour == Some(ix))
+                .map(|p| p.problem_type())
+                .collect();
+            ((gx, ix), types)
+        })
+        .collect();
+    let no_types = HashSet::new();
+
+    let mut reported = HashSet::new();
+    let mut problems = vec![];
+    for (i, (gx, ix, descriptor)) in contours.iter().enumerate() {
+        let size = descriptor[0].abs() + descriptor[4].abs();
+        for (gy, iy, other) in contours[i + 1..].iter() {
+            if other[0].abs() - descriptor[0].abs() > size * SAME_SHAPE_TOLERANCE {
+                break;
+            }
+            let distance = descriptor
+                .iter()
+                .zip(other.iter())
+                .map(|(a, b)| (a - b).powi(2))
+                .sum::<f64>()
+                .sqrt();
+            if gx == gy || distance > size * SAME_SHAPE_TOLERANCE {
+                continue;
+            }
+            let types_x = problem_types.get(&(*gx, *ix)).unwrap_or(&no_types);
+            let types_y = problem_types.get(&(*gy, *iy)).unwrap_or(&no_types);
+            for (here, there, (g_here, c_here), (g_there, c_there)) in [
+                (types_x, types_y, (*gx, *ix), (*gy, *iy)),
+                (types_y, types_x, (*gy, *iy), (*gx, *ix)),
+            ] {
+                for problem_type in here.difference(there) {
+                    if !reported.insert((g_here, c_here, problem_type.clone())) {
+                        continue;
+                    }
+                    let report = &glyphs[g_here];
+                    problems.push((
+                        report.name.to_string(),
+                        Problem::shared_shape_inconsistency(
+                            report.glyph_a,
+                            report.glyph_b,
+                            c_here,
+                            problem_type,
+                            glyphs[g_there].name,
+                            c_there,
+                        ),
+                    ));
+                }
+            }
+        }
+    }
+    problems
+}
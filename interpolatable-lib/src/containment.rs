@@ -0,0 +1,69 @@
+use kurbo::{BezPath, PathEl, Rect, Shape};
+
+use crate::{problems::Problem, Glyph};
+
+fn rect_contains(outer: &Rect, inner: &Rect) -> bool {
+    outer.x0 <= inner.x0 && outer.y0 <= inner.y0 && outer.x1 >= inner.x1 && outer.y1 >= inner.y1
+}
+
+/// Compute which contours lie inside which others
+///
+/// `result[i][j]` is true if contour `i` is inside contour `j`: its bounding
+/// box is within that of `j`, and its first point is inside `j`.
+fn containment(curves: &[&BezPath]) -> Vec<Vec<bool>> {
+    let bounds: Vec<Rect> = curves.iter().map(|c| c.bounding_box()).collect();
+    curves
+        .iter()
+        .enumerate()
+        .map(|(i, inner)| {
+            let start = match inner.elements().first() {
+                Some(PathEl::MoveTo(pt)) => Some(*pt),
+                _ => None,
+            };
+            curves
+                .iter()
+                .enumerate()
+                .map(|(j, outer)| {
+                    i != j
+                        && rect_contains(&bounds[j], &bounds[i])
+                        && start.is_some_and(|pt| outer.winding(pt) != 0)
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Test that the midpoint interpolation has the same contour nesting as the masters
+///
+/// If both masters agree that contour `i` is (or isn't) inside contour `j`
+/// but the midpoint interpolation disagrees, a counter has escaped its
+/// containing shape (or vice versa) and the glyph will fill incorrectly.
+pub(crate) fn test_containment(
+    glyph_a: &Glyph,
+    glyph_b: &Glyph,
+    m0_curves: &[BezPath],
+    m1_curves: &[BezPath],
+    midpoint_interpolations: &[Option<BezPath>],
+) -> Vec<Problem> {
+    let mut problems = vec![];
+    let Some(mid_curves) = midpoint_interpolations
+        .iter()
+        .map(|c| c.as_ref())
+        .collect::<Option<Vec<&BezPath>>>()
+    else {
+        return problems;
+    };
+    let m0 = containment(&m0_curves.iter().collect::<Vec<_>>());
+    let m1 = containment(&m1_curves.iter().collect::<Vec<_>>());
+    let mid = containment(&mid_curves);
+    for (i, ((row0, row1), row_mid)) in m0.iter().zip(m1.iter()).zip(mid.iter()).enumerate() {
+        for (j, ((&inside0, &inside1), &inside_mid)) in
+            row0.iter().zip(row1.iter()).zip(row_mid.iter()).enumerate()
+        {
+            if inside0 == inside1 && inside0 != inside_mid {
+                problems.push(Problem::containment_change(glyph_a, glyph_b, i, j, inside0));
+            }
+        }
+    }
+    problems
+}
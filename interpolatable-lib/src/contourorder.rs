@@ -1,61 +1,132 @@
+use core::fmt;
+
 use munkres::{Position, Weights};
+use serde::Serialize;
 
 use crate::{
+    contour_mapping,
+    translation::translation_offset,
     utils::{Matching, VdiffHypo2},
-    Glyph,
+    Glyph, RunTestsOptions,
 };
 
+/// Why a contour was matched with a contour at a different index in the other glyph
+///
+/// See [explain_contour_order]. The vectors are the contours' Green's theorem
+/// feature vectors (see [crate::Glyph::green_vectors]).
+#[derive(Debug, Clone, Serialize)]
+pub struct ContourMatch {
+    /// The index of the contour in the first glyph
+    pub contour_1: usize,
+    /// The index of the contour it was matched with in the second glyph
+    pub contour_2: usize,
+    pub vector_1: Vec<f64>,
+    pub vector_2: Vec<f64>,
+    /// The distance between the two contours' feature vectors
+    pub distance: f64,
+    /// The distance to the contour at the same index in the second glyph,
+    /// which is what keeping the original order would have cost
+    pub identity_distance: f64,
+}
+
+impl fmt::Display for ContourMatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "contour {} (area {:.0}, com {:.0},{:.0}) best matches contour {} (area {:.0}, com {:.0},{:.0}): distance {:.1}, against {:.1} for contour {}",
+            self.contour_1,
+            self.vector_1[0],
+            self.vector_1[1],
+            self.vector_1[2],
+            self.contour_2,
+            self.vector_2[0],
+            self.vector_2[1],
+            self.vector_2[2],
+            self.distance,
+            self.identity_distance,
+            self.contour_1,
+        )
+    }
+}
+
+/// Explain the contour order which [crate::run_tests] would choose
+///
+/// For each contour of `glyph_a` which is matched with a contour at a
+/// different index in `glyph_b`, returns the feature vectors of the two
+/// contours and how far apart they are, compared with the contour at the same
+/// index. This is the information behind a `ContourOrder` problem; the
+/// `Display` implementation gives a one-line summary, such as `contour 0 (area
+/// -347, com 281,365) best matches contour 2 (area -340, com 278,360)...`.
+/// The area is given as the signed square root of the area, as in the
+/// feature vector. Returns an empty list if the contours are in order, or
+/// can't be matched because the glyphs have different numbers of contours.
+/// Contours are matched as [crate::run_tests_with] matches them with the same
+/// `options`.
+pub fn explain_contour_order(
+    glyph_a: &Glyph,
+    glyph_b: &Glyph,
+    options: &RunTestsOptions,
+) -> Vec<ContourMatch> {
+    contour_mapping(glyph_a, glyph_b, options)
+        .into_iter()
+        .enumerate()
+        .filter(|(ix, jx)| ix != jx)
+        .filter_map(|(ix, jx)| {
+            let vector_1 = glyph_a.green_vectors.get(ix)?;
+            let vector_2 = glyph_b.green_vectors.get(jx)?;
+            let identity = glyph_b.green_vectors.get(ix)?;
+            Some(ContourMatch {
+                contour_1: ix,
+                contour_2: jx,
+                vector_1: vector_1.clone(),
+                vector_2: vector_2.clone(),
+                distance: vector_1.vdiff_hypot2(vector_2).sqrt(),
+                identity_distance: vector_1.vdiff_hypot2(identity).sqrt(),
+            })
+        })
+        .collect()
+}
+
 pub(crate) fn test_contour_order<'a>(
     glyph1: &'a Glyph,
     glyph2: &'a Glyph,
+    max_distance: Option<f64>,
 ) -> (f64, Option<Matching>) {
     let n = glyph1.control_vectors.len();
-    if n <= 1 {
+    if n <= 1 || n != glyph2.control_vectors.len() {
         return (1.0, None);
     }
 
-    let (matching_control, matching_cost_control, identity_cost_control) =
-        matching_for_vectors(&glyph1.control_vectors, &glyph2.control_vectors);
+    let (_, matching_cost_control, identity_cost_control) = matching_for_vectors(
+        &glyph1.control_vectors,
+        &glyph2.control_vectors,
+        max_distance,
+    );
     if matching_cost_control == identity_cost_control {
         return (1.0, None);
     }
 
-    let (matching_green, matching_cost_green, identity_cost_green) =
-        matching_for_vectors(&glyph1.green_vectors, &glyph2.green_vectors);
+    let (_, matching_cost_green, identity_cost_green) =
+        matching_for_vectors(&glyph1.green_vectors, &glyph2.green_vectors, max_distance);
     if matching_cost_green == identity_cost_green {
         return (1.0, None);
     }
 
-    // Maybe they're OK, but the contours are reversed.
-    let g2_control_reversed: Vec<Vec<f64>> = glyph2
-        .control_vectors
-        .iter()
-        .map(|v| {
-            // Reverse the sign of the first element
-            let mut v = v.clone();
-            v[0] = -v[0];
-            v
-        })
-        .collect();
-    let (_, matching_cost_control_reversed, identity_cost_control_reversed) =
-        matching_for_vectors(&glyph1.control_vectors, &g2_control_reversed);
-    if matching_cost_control_reversed == identity_cost_control_reversed {
+    // Maybe they're OK, but some contours are reversed. Contours may also be
+    // both moved and reversed, so let the matcher choose, for each pair of
+    // contours, whichever of the two directions matches better.
+    let (matching_control, matching_cost_control, identity_cost_control) =
+        matching_allowing_reversal(
+            &glyph1.control_vectors,
+            &glyph2.control_vectors,
+            max_distance,
+        );
+    if matching_cost_control == identity_cost_control {
         return (1.0, None);
     }
-
-    let g2_green_reversed = glyph2
-        .green_vectors
-        .iter()
-        .map(|v| {
-            // Reverse the sign of the first element
-            let mut v = v.clone();
-            v[0] = -v[0];
-            v
-        })
-        .collect();
-    let (_, matching_cost_green_reversed, identity_cost_green_reversed) =
-        matching_for_vectors(&glyph1.green_vectors, &g2_green_reversed);
-    if matching_cost_green_reversed == identity_cost_green_reversed {
+    let (matching_green, matching_cost_green, identity_cost_green) =
+        matching_allowing_reversal(&glyph1.green_vectors, &glyph2.green_vectors, max_distance);
+    if matching_cost_green == identity_cost_green {
         return (1.0, None);
     }
 
@@ -71,6 +142,15 @@ pub(crate) fn test_contour_order<'a>(
     } else {
         (matching_green, matching_cost_green, identity_cost_green)
     };
+    // A contour which is merely translated from its counterpart at the same
+    // index is certainly in the right place, even if it has moved far enough
+    // to confuse the matcher; don't trust a matching which moves it.
+    if matching.iter().any(|pos| {
+        pos.row != pos.column
+            && translation_offset(&glyph1.points[pos.row], &glyph2.points[pos.row]).is_some()
+    }) {
+        return (1.0, None);
+    }
     let this_tolerance = if identity_cost != 0.0 {
         matching_cost / identity_cost
     } else {
@@ -83,16 +163,63 @@ pub(crate) fn test_contour_order<'a>(
     (this_tolerance, Some(matching))
 }
 
-fn matching_for_vectors(m0: &Vec<Vec<f64>>, m1: &Vec<Vec<f64>>) -> (Matching, f64, f64) {
+fn matching_for_vectors(
+    m0: &[Vec<f64>],
+    m1: &[Vec<f64>],
+    max_distance: Option<f64>,
+) -> (Matching, f64, f64) {
     assert!(m0.len() == m1.len());
-    let mut weights = vec![];
-    for v0 in m0 {
-        for v1 in m1 {
-            weights.push(v0.vdiff_hypot2(v1));
-        }
+    let weights: Vec<f64> = cost_matrix(m0, m1).into_iter().flatten().collect();
+    solve_matching(weights, m0, m1, max_distance)
+}
+
+/// Match contours as [matching_for_vectors] does, but let each contour of
+/// `m1` be matched either as it is or reversed
+///
+/// Reversing a contour flips the sign of its area (the first element of its
+/// feature vector), so each cost is the cheaper of the two.
+fn matching_allowing_reversal(
+    m0: &[Vec<f64>],
+    m1: &[Vec<f64>],
+    max_distance: Option<f64>,
+) -> (Matching, f64, f64) {
+    assert!(m0.len() == m1.len());
+    let m1_reversed: Vec<Vec<f64>> = m1
+        .iter()
+        .map(|v| {
+            // Reverse the sign of the first element
+            let mut v = v.clone();
+            v[0] = -v[0];
+            v
+        })
+        .collect();
+    let weights: Vec<f64> = cost_matrix(m0, m1)
+        .into_iter()
+        .flatten()
+        .zip(cost_matrix(m0, &m1_reversed).into_iter().flatten())
+        .map(|(forward, reversed)| forward.min(reversed))
+        .collect();
+    solve_matching(weights, m0, m1, max_distance)
+}
+
+/// Find the cheapest assignment for a flattened cost matrix
+///
+/// Returns the matching, its cost, and the cost of keeping the contours in
+/// their original order.
+fn solve_matching(
+    mut weights: Vec<f64>,
+    m0: &[Vec<f64>],
+    m1: &[Vec<f64>],
+    max_distance: Option<f64>,
+) -> (Matching, f64, f64) {
+    if let Some(max_distance) = max_distance {
+        inflate_distant_cells(&mut weights, m0, m1, max_distance);
     }
-    let mut costs = munkres::WeightMatrix::from_row_vec(m0.len(), weights);
-    if let Ok(matching) = munkres::solve_assignment(&mut costs) {
+    let mut costs = munkres::WeightMatrix::from_row_vec(m0.len(), weights.clone());
+    if let Ok(mut matching) = munkres::solve_assignment(&mut costs) {
+        // Keep the positions in row order so that reordering lines up with the first glyph
+        matching.sort_by_key(|pos| pos.row);
+        prefer_identity_on_ties(&mut matching, &weights, m1.len());
         let matching_cost = matching.iter().map(|pos| costs.element_at(*pos)).sum();
         let identity_cost = (0..m0.len())
             .map(|i| costs.element_at(Position { row: i, column: i }))
@@ -102,3 +229,71 @@ fn matching_for_vectors(m0: &Vec<Vec<f64>>, m1: &Vec<Vec<f64>>) -> (Matching, f6
         (Matching(vec![]), 0.0, 0.0)
     }
 }
+
+/// The cost of matching each vector in `m0` with each vector in `m1`
+fn cost_matrix(m0: &[Vec<f64>], m1: &[Vec<f64>]) -> Vec<Vec<f64>> {
+    m0.iter()
+        .map(|v0| m1.iter().map(|v1| v0.vdiff_hypot2(v1)).collect())
+        .collect()
+}
+
+/// The costs the contour order check uses to match contours, for debugging
+///
+/// Element `[i][j]` is the cost of matching contour `i` of `glyph_a` with
+/// contour `j` of `glyph_b`: the squared distance between their Green's
+/// theorem feature vectors. The matcher picks the assignment with the lowest
+/// total cost (it also checks the control point vectors, and doesn't apply
+/// the `max_contour_distance` penalty here), so this shows why one contour
+/// was preferred to another.
+pub fn contour_order_costs(glyph_a: &Glyph, glyph_b: &Glyph) -> Vec<Vec<f64>> {
+    cost_matrix(&glyph_a.green_vectors, &glyph_b.green_vectors)
+}
+
+/// Costs which differ by less than this proportion are considered tied
+const TIE_EPSILON: f64 = 1e-9;
+
+/// Among equally cheap matchings, prefer the one closest to the identity
+///
+/// Symmetric glyphs often have several optimal matchings; without this the
+/// solver's choice between them is arbitrary, and we might suggest a contour
+/// order change which isn't needed. We repeatedly swap the columns of two
+/// rows if that puts more contours back in their original place without
+/// increasing the total cost. `matching` must be sorted by row.
+pub(crate) fn prefer_identity_on_ties(matching: &mut [Position], weights: &[f64], width: usize) {
+    let weight = |row: usize, column: usize| weights[row * width + column];
+    let fixed = |row: usize, column: usize| (row == column) as usize;
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for i in 0..matching.len() {
+            for j in i + 1..matching.len() {
+                let (ci, cj) = (matching[i].column, matching[j].column);
+                if fixed(i, cj) + fixed(j, ci) <= fixed(i, ci) + fixed(j, cj) {
+                    continue;
+                }
+                let before = weight(i, ci) + weight(j, cj);
+                let after = weight(i, cj) + weight(j, ci);
+                if after <= before + TIE_EPSILON * before.abs().max(1.0) {
+                    matching[i].column = cj;
+                    matching[j].column = ci;
+                    changed = true;
+                }
+            }
+        }
+    }
+}
+
+/// Make matchings between contours whose centers of mass are further apart
+/// than `max_distance` so expensive that the solver will never choose them.
+///
+/// The diagonal is left alone so that the identity cost is unaffected.
+fn inflate_distant_cells(weights: &mut [f64], m0: &[Vec<f64>], m1: &[Vec<f64>], max_distance: f64) {
+    let penalty = weights.iter().copied().fold(0.0, f64::max) * m0.len() as f64 + 1.0;
+    for (i, v0) in m0.iter().enumerate() {
+        for (j, v1) in m1.iter().enumerate() {
+            if i != j && (v0[1] - v1[1]).hypot(v0[2] - v1[2]) > max_distance {
+                weights[i * m1.len() + j] += penalty;
+            }
+        }
+    }
+}
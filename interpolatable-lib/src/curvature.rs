@@ -0,0 +1,65 @@
+use kurbo::{PathSeg, Vec2};
+
+use crate::{
+    basiccompat::test_compatibility, contour_mapping, problems::Problem, Glyph, RunTestsOptions,
+};
+
+/// Segments whose handles are within this sine of being parallel are too
+/// straight for the way they bend to matter
+const FLAT_SINE: f64 = 0.05;
+
+/// Which way a segment bends: 1 for anticlockwise, -1 for clockwise, or `None`
+/// if it is (nearly) straight
+///
+/// This is the sign of the cross product of the handle vectors: from the
+/// start point to its handle, and from the end point's handle to the end point.
+fn bend_direction(segment: PathSeg) -> Option<i8> {
+    let (handle_in, handle_out): (Vec2, Vec2) = match segment {
+        PathSeg::Cubic(c) => (c.p1 - c.p0, c.p3 - c.p2),
+        PathSeg::Quad(q) => (q.p1 - q.p0, q.p2 - q.p1),
+        PathSeg::Line(_) => return None,
+    };
+    let sine = handle_in.cross(handle_out) / (handle_in.length() * handle_out.length());
+    if sine.is_nan() || sine.abs() < FLAT_SINE {
+        return None;
+    }
+    Some(if sine > 0.0 { 1 } else { -1 })
+}
+
+/// Test that each curve segment bends the same way in both masters
+///
+/// A segment whose end points match can still have its handles arranged so
+/// that it bulges one way in one master and the other way in the other, so
+/// that the curve caves in somewhere along the interpolation. Curved segments
+/// (of any order) which bend in opposite directions are reported as
+/// `SegmentCurvatureFlip` problems, with the index of the segment in the
+/// contour and the direction of bending in each master. Segments which are
+/// nearly straight in either master are ignored. Contours are matched as
+/// [crate::run_tests_with] matches them with the same `options`.
+pub fn test_segment_curvature(
+    glyph_a: &Glyph,
+    glyph_b: &Glyph,
+    options: &RunTestsOptions,
+) -> Vec<Problem> {
+    let mut problems = vec![];
+    if !test_compatibility(glyph_a, glyph_b).is_empty() {
+        return problems;
+    }
+    let mapping = contour_mapping(glyph_a, glyph_b, options);
+    for (ix, &jx) in mapping.iter().enumerate() {
+        let (Some(c0), Some(c1)) = (glyph_a.curves.get(ix), glyph_b.curves.get(jx)) else {
+            continue;
+        };
+        for (segment, (seg0, seg1)) in c0.segments().zip(c1.segments()).enumerate() {
+            let (Some(sign_1), Some(sign_2)) = (bend_direction(seg0), bend_direction(seg1)) else {
+                continue;
+            };
+            if sign_1 != sign_2 {
+                problems.push(Problem::segment_curvature_flip(
+                    glyph_a, glyph_b, ix, segment, sign_1, sign_2,
+                ));
+            }
+        }
+    }
+    problems
+}
@@ -0,0 +1,59 @@
+use serde::{Deserialize, Serialize};
+
+use crate::Glyph;
+
+/// A summary of one way of aligning a contour's points with another's
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IsomorphismSummary {
+    pub rotation: usize,
+    pub reverse: bool,
+}
+
+/// The precomputed features of a glyph, suitable for persisting
+///
+/// This holds the feature vectors which drive contour matching, plus a
+/// summary of each contour's isomorphisms, without the outlines or the
+/// (large) rotated characteristic vectors. With the `postcard` feature
+/// enabled, it can be serialized to a compact binary form with
+/// [GlyphDescriptors::to_bytes].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GlyphDescriptors {
+    pub green_vectors: Vec<Vec<f64>>,
+    pub control_vectors: Vec<Vec<f64>>,
+    pub isomorphisms: Vec<Vec<IsomorphismSummary>>,
+}
+
+impl From<&Glyph> for GlyphDescriptors {
+    fn from(glyph: &Glyph) -> Self {
+        GlyphDescriptors {
+            green_vectors: glyph.green_vectors.clone(),
+            control_vectors: glyph.control_vectors.clone(),
+            isomorphisms: glyph
+                .isomorphisms
+                .iter()
+                .map(|isomorphisms| {
+                    isomorphisms
+                        .iter()
+                        .map(|c| IsomorphismSummary {
+                            rotation: c.rotation,
+                            reverse: c.reverse,
+                        })
+                        .collect()
+                })
+                .collect(),
+        }
+    }
+}
+
+#[cfg(feature = "postcard")]
+impl GlyphDescriptors {
+    /// Serialize the descriptors to a compact binary format
+    pub fn to_bytes(&self) -> Result<Vec<u8>, postcard::Error> {
+        postcard::to_allocvec(self)
+    }
+
+    /// Deserialize descriptors previously serialized with [GlyphDescriptors::to_bytes]
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, postcard::Error> {
+        postcard::from_bytes(bytes)
+    }
+}
@@ -0,0 +1,60 @@
+use kurbo::Shape;
+
+use crate::{
+    basiccompat::test_compatibility, contour_mapping, problems::Problem, utils::lerp_curve_at,
+    Glyph, RunTestsOptions,
+};
+
+/// A contour whose area falls below this proportion of the smaller master's has collapsed
+const COLLAPSE_RATIO: f64 = 0.05;
+
+/// Test for contours which collapse or turn inside out when extrapolated
+///
+/// Most fonts are only designed to be used between their masters, but some
+/// are used beyond them (for example, in animation). Extrapolating linearly
+/// can make a thin contour shrink to nothing and then turn inside out, even
+/// though it interpolates perfectly well. This check extrapolates each pair
+/// of matched contours `amount` beyond each master (that is, to
+/// `t = -amount` and `t = 1 + amount`) and reports an
+/// `ExtrapolationCollapse` problem for any contour whose area falls below 5%
+/// of the smaller master's, or changes sign.
+///
+/// This is an advanced check: don't run it unless the font is meant to be
+/// extrapolated. It is not run by [crate::run_tests]. Contours are matched as
+/// [crate::run_tests_with] matches them with the same `options`.
+pub fn test_extrapolation(
+    glyph_a: &Glyph,
+    glyph_b: &Glyph,
+    amount: f64,
+    options: &RunTestsOptions,
+) -> Vec<Problem> {
+    let mut problems = vec![];
+    if !test_compatibility(glyph_a, glyph_b).is_empty() {
+        return problems;
+    }
+    let mapping = contour_mapping(glyph_a, glyph_b, options);
+    for (ix, &jx) in mapping.iter().enumerate() {
+        let (Some(c0), Some(c1)) = (glyph_a.curves.get(ix), glyph_b.curves.get(jx)) else {
+            continue;
+        };
+        let (area0, area1) = (c0.area(), c1.area());
+        if area0 == 0.0 || area1 == 0.0 || (area0 < 0.0) != (area1 < 0.0) {
+            // Degenerate or reversed contours are reported by other checks
+            continue;
+        }
+        let smaller = area0.abs().min(area1.abs());
+        for t in [-amount, 1.0 + amount] {
+            let Some(extrapolated) = lerp_curve_at(c0, c1, t) else {
+                continue;
+            };
+            // Negative if the contour has turned inside out
+            let ratio = extrapolated.area() * area0.signum() / smaller;
+            if ratio < COLLAPSE_RATIO {
+                problems.push(Problem::extrapolation_collapse(
+                    glyph_a, glyph_b, ix, t, ratio,
+                ));
+            }
+        }
+    }
+    problems
+}
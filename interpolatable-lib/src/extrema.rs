@@ -0,0 +1,49 @@
+use kurbo::{BezPath, ParamCurve, ParamCurveExtrema, Rect};
+
+use crate::{problems::Problem, Glyph};
+
+/// How far (in font units) a curve may bulge beyond its end points before we
+/// consider it to be missing an extremum point
+const EXTREMUM_TOLERANCE: f64 = 1.0;
+/// How many more segments one master must be missing extrema on than the
+/// other before we report it; a single differing segment is often deliberate
+const MIN_MISSING_DIFFERENCE: usize = 2;
+
+/// Count the segments of a contour which have an extremum between their on-curve points
+fn missing_extrema(contour: &BezPath) -> usize {
+    contour
+        .segments()
+        .filter(|seg| {
+            let ends = Rect::from_points(seg.start(), seg.end());
+            let bbox = seg.bounding_box();
+            bbox.x0 < ends.x0 - EXTREMUM_TOLERANCE
+                || bbox.y0 < ends.y0 - EXTREMUM_TOLERANCE
+                || bbox.x1 > ends.x1 + EXTREMUM_TOLERANCE
+                || bbox.y1 > ends.y1 + EXTREMUM_TOLERANCE
+        })
+        .count()
+}
+
+/// Test whether one master has on-curve points at extrema where the other doesn't
+///
+/// The masters remain compatible, but the master without extremum points
+/// will generally be of poorer quality, so this is reported as an advisory
+/// `MissingExtrema` problem. Small differences are tolerated, since a
+/// designer may reasonably drop an extremum point from one master.
+pub(crate) fn test_extrema<'a>(
+    glyph_a: &'a Glyph,
+    glyph_b: &'a Glyph,
+    contour0: &BezPath,
+    contour1: &BezPath,
+    ix: usize,
+) -> Option<Problem> {
+    let missing_1 = missing_extrema(contour0);
+    let missing_2 = missing_extrema(contour1);
+    if missing_1.abs_diff(missing_2) >= MIN_MISSING_DIFFERENCE {
+        Some(Problem::missing_extrema(
+            glyph_a, glyph_b, ix, missing_1, missing_2,
+        ))
+    } else {
+        None
+    }
+}
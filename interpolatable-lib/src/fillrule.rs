@@ -0,0 +1,71 @@
+use kurbo::{BezPath, Point, Shape};
+
+use crate::{problems::Problem, Glyph};
+
+/// The number of samples along each side of the glyph's bounding box when measuring its filled area
+const GRID_SIZE: usize = 48;
+/// How far (as a proportion) the filled area at the midpoint may stray from
+/// the average of the masters' filled areas
+const FILL_TOLERANCE: f64 = 0.25;
+
+/// Estimate the area filled by a set of contours under the non-zero winding rule
+///
+/// Overlapping contours and counters mean this isn't the same as the sum of
+/// the contours' areas, so we sample the winding number across the bounding box.
+fn filled_area(curves: &[BezPath]) -> f64 {
+    let Some(bbox) = curves
+        .iter()
+        .map(|c| c.bounding_box())
+        .reduce(|a, b| a.union(b))
+    else {
+        return 0.0;
+    };
+    let dx = bbox.width() / GRID_SIZE as f64;
+    let dy = bbox.height() / GRID_SIZE as f64;
+    let mut filled = 0;
+    for i in 0..GRID_SIZE {
+        for j in 0..GRID_SIZE {
+            let pt = Point::new(
+                bbox.x0 + (i as f64 + 0.5) * dx,
+                bbox.y0 + (j as f64 + 0.5) * dy,
+            );
+            if curves.iter().map(|c| c.winding(pt)).sum::<i32>() != 0 {
+                filled += 1;
+            }
+        }
+    }
+    filled as f64 * dx * dy
+}
+
+/// Test whether the midway interpolation fills a very different region from the masters
+///
+/// Per-contour checks can miss problems which only show up when the contours
+/// are filled together: a contour changing direction relative to its
+/// neighbours, say, so that a counter fills in or an overlap becomes a hole.
+/// We compare the area filled (under the non-zero rule, as used by TrueType
+/// and CFF) at the midpoint with the average of the masters' filled areas, and
+/// report a `FillRuleArtifact` if they differ by more than a quarter.
+pub(crate) fn test_fill_rule<'a>(
+    glyph_a: &'a Glyph,
+    glyph_b: &'a Glyph,
+    m0_curves: &[BezPath],
+    m1_curves: &[BezPath],
+    midpoint_interpolations: &[Option<BezPath>],
+) -> Option<Problem> {
+    let midpoint: Vec<BezPath> = midpoint_interpolations
+        .iter()
+        .cloned()
+        .collect::<Option<_>>()?;
+    let expected = (filled_area(m0_curves) + filled_area(m1_curves)) / 2.0;
+    if expected == 0.0 {
+        return None;
+    }
+    let actual = filled_area(&midpoint);
+    if (actual / expected - 1.0).abs() > FILL_TOLERANCE {
+        Some(Problem::fill_rule_artifact(
+            glyph_a, glyph_b, actual, expected,
+        ))
+    } else {
+        None
+    }
+}
@@ -0,0 +1,99 @@
+use kurbo::Point;
+
+use crate::{
+    basiccompat::test_compatibility, contour_mapping, problems::Problem, Glyph, RunTestsOptions,
+};
+
+/// Matched contours whose normalized shapes are further apart than this are reported
+const MAX_FRECHET_DISTANCE: f64 = 0.5;
+
+/// Move a contour's points to be centered on its center of mass, with unit
+/// spread in each direction, so that only its shape remains
+fn normalize(points: &[Point], vector: &[f64]) -> Option<Vec<Point>> {
+    let (scale_x, scale_y) = (vector[3], vector[4]);
+    if scale_x == 0.0 || scale_y == 0.0 {
+        return None;
+    }
+    Some(
+        points
+            .iter()
+            .map(|pt| Point::new((pt.x - vector[1]) / scale_x, (pt.y - vector[2]) / scale_y))
+            .collect(),
+    )
+}
+
+/// The discrete Fréchet distance between two polylines
+///
+/// This is the classic dynamic programming formulation, which takes
+/// O(n·m) time and space.
+pub(crate) fn discrete_frechet(a: &[Point], b: &[Point]) -> Option<f64> {
+    if a.is_empty() || b.is_empty() {
+        return None;
+    }
+    let mut table = vec![vec![0.0f64; b.len()]; a.len()];
+    for (i, pt_a) in a.iter().enumerate() {
+        for (j, pt_b) in b.iter().enumerate() {
+            let distance = pt_a.distance(*pt_b);
+            table[i][j] = match (i, j) {
+                (0, 0) => distance,
+                (0, _) => table[0][j - 1].max(distance),
+                (_, 0) => table[i - 1][0].max(distance),
+                _ => table[i - 1][j]
+                    .min(table[i - 1][j - 1])
+                    .min(table[i][j - 1])
+                    .max(distance),
+            };
+        }
+    }
+    Some(table[a.len() - 1][b.len() - 1])
+}
+
+/// Compare the shapes of matched contours using the discrete Fréchet distance
+///
+/// The contour order and weight checks in [crate::run_tests] compare contours
+/// using a handful of statistics (area, center of mass, spread and so on),
+/// which can be the same for quite different shapes. This check compares the
+/// contours' points directly: each contour is normalized to remove its
+/// position and its scale in each direction, and the Fréchet distance between
+/// the normalized point sequences is measured. Contours further apart than
+/// half their spread are reported as `FrechetDistance` problems.
+///
+/// This is much slower than the statistical checks, so it is not run by
+/// [crate::run_tests]. The distance depends on where each contour starts, so
+/// contours with a wrong start point will usually be reported here as well.
+/// Contours are matched as [crate::run_tests_with] matches them with the same
+/// `options`.
+pub fn test_frechet_distance(
+    glyph_a: &Glyph,
+    glyph_b: &Glyph,
+    options: &RunTestsOptions,
+) -> Vec<Problem> {
+    let mut problems = vec![];
+    if !test_compatibility(glyph_a, glyph_b).is_empty() {
+        return problems;
+    }
+    let mapping = contour_mapping(glyph_a, glyph_b, options);
+    for (ix, &jx) in mapping.iter().enumerate() {
+        let (Some(contour0), Some(contour1), Some(vector0), Some(vector1)) = (
+            glyph_a.points.get(ix),
+            glyph_b.points.get(jx),
+            glyph_a.green_vectors.get(ix),
+            glyph_b.green_vectors.get(jx),
+        ) else {
+            continue;
+        };
+        let points0: Vec<Point> = contour0.iter().map(|pt| pt.point).collect();
+        let points1: Vec<Point> = contour1.iter().map(|pt| pt.point).collect();
+        let (Some(shape0), Some(shape1)) =
+            (normalize(&points0, vector0), normalize(&points1, vector1))
+        else {
+            continue;
+        };
+        if let Some(distance) = discrete_frechet(&shape0, &shape1) {
+            if distance > MAX_FRECHET_DISTANCE {
+                problems.push(Problem::frechet_distance(glyph_a, glyph_b, ix, distance));
+            }
+        }
+    }
+    problems
+}
@@ -0,0 +1,72 @@
+use crate::{problems::Problem, GlyfPoint, Glyph};
+
+/// Handles whose length relative to the chord changes by more than this factor between masters are reported...
+const MAX_RATIO_FACTOR: f64 = 3.0;
+/// ...as long as the ratio changes by at least this much
+const MIN_RATIO_CHANGE: f64 = 0.2;
+/// Segments with chords shorter than this (in font units) are ignored
+const MIN_CHORD_LENGTH: f64 = 1.0;
+
+/// The ratios of a cubic segment's two handle lengths to its chord length
+fn handle_ratios(points: &[&GlyfPoint; 4]) -> Option<[f64; 2]> {
+    let chord = (points[3].point - points[0].point).length();
+    if chord < MIN_CHORD_LENGTH {
+        return None;
+    }
+    Some([
+        (points[1].point - points[0].point).length() / chord,
+        (points[3].point - points[2].point).length() / chord,
+    ])
+}
+
+fn diverges(ratio_1: f64, ratio_2: f64) -> bool {
+    let (low, high) = (ratio_1.min(ratio_2), ratio_1.max(ratio_2));
+    high - low >= MIN_RATIO_CHANGE && (low == 0.0 || high / low > MAX_RATIO_FACTOR)
+}
+
+/// Test for cubic handles which change length drastically between masters
+///
+/// A handle which is very short in one master and very long in the other makes
+/// the curve appear to lurch during interpolation, even if there is no kink.
+/// For each cubic segment we compare the length of each handle, as a
+/// proportion of the segment's chord, between the masters; if it changes by a
+/// factor of more than three (and by at least a fifth of the chord), a
+/// `HandleLength` problem is reported on the segment's first node.
+pub(crate) fn test_handle_length<'a>(
+    glyph_a: &'a Glyph,
+    glyph_b: &'a Glyph,
+    contour0: &[GlyfPoint],
+    contour1: &[GlyfPoint],
+    ix: usize,
+) -> Vec<Problem> {
+    let mut problems = vec![];
+    let len = contour0.len();
+    if len < 4 || contour1.len() != len {
+        return problems;
+    }
+    for i in 0..len {
+        let segment0 = [0, 1, 2, 3].map(|j| &contour0[(i + j) % len]);
+        let segment1 = [0, 1, 2, 3].map(|j| &contour1[(i + j) % len]);
+        let is_cubic = |s: &[&GlyfPoint; 4]| {
+            s[0].is_control && !s[1].is_control && !s[2].is_control && s[3].is_control
+        };
+        if !is_cubic(&segment0) || !is_cubic(&segment1) {
+            continue;
+        }
+        let (Some(ratios_1), Some(ratios_2)) = (handle_ratios(&segment0), handle_ratios(&segment1))
+        else {
+            continue;
+        };
+        if let Some((ratio_1, ratio_2)) = ratios_1
+            .into_iter()
+            .zip(ratios_2)
+            .filter(|&(r1, r2)| diverges(r1, r2))
+            .max_by(|a, b| (a.0 - a.1).abs().total_cmp(&(b.0 - b.1).abs()))
+        {
+            problems.push(Problem::handle_length(
+                glyph_a, glyph_b, ix, i, ratio_1, ratio_2,
+            ));
+        }
+    }
+    problems
+}
@@ -0,0 +1,31 @@
+use kurbo::Point;
+
+use crate::Glyph;
+
+/// The furthest any point of `a` is from its nearest point in `b`
+fn directed_hausdorff(a: &[Point], b: &[Point]) -> f64 {
+    a.iter()
+        .map(|pt_a| {
+            b.iter()
+                .map(|pt_b| pt_a.distance(*pt_b))
+                .fold(f64::INFINITY, f64::min)
+        })
+        .fold(0.0, f64::max)
+}
+
+/// The Hausdorff distance between two glyphs' outlines, in font units
+///
+/// This is the furthest any point (on-curve or off-curve) of either glyph is
+/// from the nearest point of the other: an absolute measure of how far apart
+/// two masters are, independent of the statistics which [crate::run_tests]
+/// compares. Unlike the other checks, the glyphs don't need to be compatible.
+/// It takes O(n·m) time in the number of points, so it is best kept for
+/// accurate runs. Returns `None` if either glyph has no points.
+pub fn hausdorff_distance(glyph_a: &Glyph, glyph_b: &Glyph) -> Option<f64> {
+    let points_a: Vec<Point> = glyph_a.points.iter().flatten().map(|pt| pt.point).collect();
+    let points_b: Vec<Point> = glyph_b.points.iter().flatten().map(|pt| pt.point).collect();
+    if points_a.is_empty() || points_b.is_empty() {
+        return None;
+    }
+    Some(directed_hausdorff(&points_a, &points_b).max(directed_hausdorff(&points_b, &points_a)))
+}
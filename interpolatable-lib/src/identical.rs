@@ -0,0 +1,30 @@
+use crate::{problems::Problem, Glyph};
+
+/// Test whether a master is identical to the default
+///
+/// A master whose variation deltas are all zero draws exactly the same
+/// outline as the default, which is usually a mistake in the sources (an
+/// empty or unedited master). Such a master interpolates perfectly, so
+/// [crate::run_tests] would never notice; this reports it as an
+/// `IdenticalMaster` advisory. The feature vectors are compared first, as a
+/// cheap filter, and a match is confirmed by comparing every point.
+pub fn test_identical_master(default: &Glyph, master: &Glyph) -> Option<Problem> {
+    if default.curves.is_empty()
+        || default.green_vectors != master.green_vectors
+        || default.points.len() != master.points.len()
+    {
+        return None;
+    }
+    let identical = default
+        .points
+        .iter()
+        .zip(master.points.iter())
+        .all(|(contour0, contour1)| {
+            contour0.len() == contour1.len()
+                && contour0
+                    .iter()
+                    .zip(contour1.iter())
+                    .all(|(pt0, pt1)| pt0.point == pt1.point && pt0.is_control == pt1.is_control)
+        });
+    identical.then(|| Problem::identical_master(default, master))
+}
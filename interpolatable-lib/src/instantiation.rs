@@ -0,0 +1,194 @@
+use skrifa::{
+    instance::Location, prelude::NormalizedCoord, setting::VariationSetting, FontRef, GlyphId,
+    MetadataProvider,
+};
+
+use crate::{basiccompat::test_compatibility, problems::Problem, utils::glyph_variations, Glyph};
+
+/// Points further than this (in font units) from where interpolation puts them are reported
+const INSTANTIATION_TOLERANCE: f64 = 1.0;
+
+/// Positions closer than this (in normalized axis units) are the same
+const SAME_POSITION: f32 = 1e-3;
+
+/// Test that the font's own instance halfway between two locations matches
+/// the interpolation of the outlines at those locations
+///
+/// The other checks assume that the outline at a location between two
+/// masters is the linear interpolation of the masters' points. The font's
+/// variation data may not agree: intermediate regions in `gvar`, or CFF2
+/// `blend` operators referring to several regions, can move points along a
+/// curved path. This instantiates the glyph at the midpoint of the two
+/// locations (in normalized coordinates) and compares each point with the
+/// midpoint of the corresponding points at the two locations, reporting an
+/// `InstantiationDivergence` problem for each contour where a point is more
+/// than a unit away. It works the same for TrueType and CFF2 outlines.
+pub fn test_instantiation(
+    font: &FontRef,
+    glyph_id: GlyphId,
+    location_a: &[VariationSetting],
+    location_b: &[VariationSetting],
+) -> Vec<Problem> {
+    let axes = font.axes();
+    let (loc_a, loc_b) = (axes.location(location_a), axes.location(location_b));
+    let mut loc_mid = Location::new(loc_a.coords().len());
+    for ((mid, a), b) in loc_mid
+        .coords_mut()
+        .iter_mut()
+        .zip(loc_a.coords())
+        .zip(loc_b.coords())
+    {
+        *mid = NormalizedCoord::from_f32((a.to_f32() + b.to_f32()) * 0.5);
+    }
+    let (Some(mut glyph_a), Some(mut glyph_b), Some(mut glyph_mid)) = (
+        Glyph::new_from_font(font, glyph_id, location_a),
+        Glyph::new_from_font(font, glyph_id, location_b),
+        Glyph::new_from_font_at(font, glyph_id, &loc_mid, None),
+    ) else {
+        return vec![];
+    };
+    glyph_a.master_name = "location 1".to_string();
+    glyph_b.master_name = "location 2".to_string();
+    glyph_mid.master_name = "midway".to_string();
+    test_divergence(&glyph_a, &glyph_b, &glyph_mid, 0.5)
+}
+
+/// Compare each point of `instance` with the point `t` of the way from
+/// `glyph_a` to `glyph_b`, reporting contours where they are too far apart
+fn test_divergence(glyph_a: &Glyph, glyph_b: &Glyph, instance: &Glyph, t: f64) -> Vec<Problem> {
+    let mut problems = vec![];
+    if !test_compatibility(glyph_a, glyph_b).is_empty()
+        || !test_compatibility(glyph_a, instance).is_empty()
+    {
+        return problems;
+    }
+    for (ix, ((contour_a, contour_b), contour_instance)) in glyph_a
+        .points
+        .iter()
+        .zip(glyph_b.points.iter())
+        .zip(instance.points.iter())
+        .enumerate()
+    {
+        let worst = contour_a
+            .iter()
+            .zip(contour_b.iter())
+            .zip(contour_instance.iter())
+            .map(|((a, b), pt)| a.point.lerp(b.point, t).distance(pt.point))
+            .enumerate()
+            .max_by(|x, y| x.1.total_cmp(&y.1));
+        if let Some((node, distance)) = worst {
+            if distance > INSTANTIATION_TOLERANCE {
+                problems.push(Problem::instantiation_divergence(
+                    glyph_a, glyph_b, ix, node, distance,
+                ));
+            }
+        }
+    }
+    problems
+}
+
+/// How far along the line from `a` to `b` (in normalized coordinates) the
+/// location `target` is, if it lies strictly between them
+fn position_between(a: &Location, b: &Location, target: &Location) -> Option<f32> {
+    let coords = |loc: &Location| loc.coords().iter().map(|c| c.to_f32()).collect::<Vec<_>>();
+    let (a, b, target) = (coords(a), coords(b), coords(target));
+    let direction: Vec<f32> = b.iter().zip(a.iter()).map(|(b, a)| b - a).collect();
+    let offset: Vec<f32> = target.iter().zip(a.iter()).map(|(p, a)| p - a).collect();
+    let length2: f32 = direction.iter().map(|d| d * d).sum();
+    if length2 == 0.0 {
+        return None;
+    }
+    let t = offset
+        .iter()
+        .zip(direction.iter())
+        .map(|(o, d)| o * d)
+        .sum::<f32>()
+        / length2;
+    let off_line = offset
+        .iter()
+        .zip(direction.iter())
+        .map(|(o, d)| (o - t * d).powi(2))
+        .sum::<f32>()
+        .sqrt();
+    (t > SAME_POSITION && t < 1.0 - SAME_POSITION && off_line < SAME_POSITION).then_some(t)
+}
+
+/// Test that the font's own outline at each named instance matches the
+/// interpolation of the glyph's masters on either side of it
+///
+/// Named instances are the locations users actually pick, so this checks
+/// [test_instantiation]'s assumption there rather than only at midpoints.
+/// For each named instance, the nearest pair of masters (from the glyph's
+/// `gvar` tuples, plus the default) which the instance lies directly between
+/// is found, and the instance's outline is compared with the interpolation of
+/// those two masters. Instances which don't lie on a line between two
+/// masters (such as instances in the middle of a two-axis design space) and
+/// instances at a master are skipped. Returns the problems found for each
+/// instance, by the instance's name.
+pub fn test_named_instances(font: &FontRef, glyph_id: GlyphId) -> Vec<(String, Vec<Problem>)> {
+    let mut results = vec![];
+    let Ok(variations) = glyph_variations(font, glyph_id) else {
+        return results;
+    };
+    let axes = font.axes();
+    let masters: Vec<(Vec<VariationSetting>, Location)> = std::iter::once(vec![])
+        .chain(variations)
+        .map(|loc| {
+            let normalized = axes.location(&loc);
+            (loc, normalized)
+        })
+        .collect();
+    for instance in font.named_instances().iter() {
+        let name = font
+            .localized_strings(instance.subfamily_name_id())
+            .english_or_first()
+            .map(|s| s.to_string())
+            .unwrap_or_else(|| "instance".to_string());
+        let location = instance.location();
+        let mut best: Option<(usize, usize, f32, f32)> = None;
+        for (i, (_, a)) in masters.iter().enumerate() {
+            for (j, (_, b)) in masters.iter().enumerate().skip(i + 1) {
+                let Some(t) = position_between(a, b, &location) else {
+                    continue;
+                };
+                let span = a
+                    .coords()
+                    .iter()
+                    .zip(b.coords())
+                    .map(|(a, b)| (b.to_f32() - a.to_f32()).powi(2))
+                    .sum::<f32>();
+                if best.is_some_and(|(_, _, _, best_span)| best_span <= span) {
+                    continue;
+                }
+                best = Some((i, j, t, span));
+            }
+        }
+        let Some((i, j, t, _)) = best else {
+            continue;
+        };
+        let describe = |loc: &[VariationSetting]| {
+            if loc.is_empty() {
+                "default".to_string()
+            } else {
+                loc.iter()
+                    .map(|v| format!("{}={}", v.selector, v.value))
+                    .collect::<Vec<_>>()
+                    .join(",")
+            }
+        };
+        let (Some(mut glyph_a), Some(mut glyph_b), Some(glyph_instance)) = (
+            Glyph::new_from_font(font, glyph_id, &masters[i].0),
+            Glyph::new_from_font(font, glyph_id, &masters[j].0),
+            Glyph::new_from_font_at(font, glyph_id, &location, None),
+        ) else {
+            continue;
+        };
+        glyph_a.master_name = describe(&masters[i].0);
+        glyph_b.master_name = describe(&masters[j].0);
+        let problems = test_divergence(&glyph_a, &glyph_b, &glyph_instance, t as f64);
+        if !problems.is_empty() {
+            results.push((name, problems));
+        }
+    }
+    results
+}
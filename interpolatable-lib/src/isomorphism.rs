@@ -1,17 +1,32 @@
 use kurbo::Vec2;
+use serde::{Deserialize, Serialize};
 
 use crate::GlyfPoint;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub(crate) struct Characteristic {
     pub rotated_list: Vec<Vec2>,
     pub rotation: usize,
     pub reverse: bool,
 }
-#[derive(Default, Debug, Clone)]
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
 pub(crate) struct Isomorphisms(pub(crate) Vec<Characteristic>);
 
-fn points_complex_vector(points: Vec<GlyfPoint>) -> Vec<Vec2> {
+/// Compute the complex characteristic vector of a contour
+///
+/// This is the rotation-invariant description of a contour used to match
+/// start points between masters. For each point in the contour, four
+/// entries are produced:
+///
+/// 1. The position of the point itself
+/// 2. The vector to the next point, multiplied by 3
+/// 3. The turn vector, i.e. the difference between the next edge and this one
+/// 4. The signed square root of the cross product of this edge and the next
+///    (a measure of the angle turned), multiplied by 4, stored in the `x`
+///    component with `y` set to zero
+///
+/// Rotating the input contour by `n` points rotates the output by `4 * n` entries.
+pub fn contour_characteristic_vector(points: &[GlyfPoint]) -> Vec<Vec2> {
     let mut vector = Vec::with_capacity(points.len() * 4);
     let len = points.len();
     let cycle_index = |x| x % len;
@@ -43,15 +58,10 @@ fn points_characteristic_bits<'a>(
 
 impl Isomorphisms {
     pub(crate) fn new(points: &[GlyfPoint]) -> Self {
-        let points = if points.len() > 1
-            && points.first() == points.last()
-            && points.first().map(|x| x.is_control) == Some(true)
-        {
-            &points[0..points.len() - 1]
-        } else {
-            &points[0..points.len()]
-        };
         let mut isomorphism = Self::default();
+        if points.is_empty() {
+            return isomorphism;
+        }
         isomorphism.add(points, false);
         isomorphism.add(points, true);
         isomorphism
@@ -70,7 +80,7 @@ impl Isomorphisms {
         } else {
             reference_bits.clone()
         };
-        let vector = points_complex_vector(points);
+        let vector = contour_characteristic_vector(&points);
         assert_eq!(vector.len() % n, 0);
         let mult: usize = vector.len() / n;
 
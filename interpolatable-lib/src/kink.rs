@@ -1,26 +1,123 @@
-use crate::{problems::Problem, GlyfPoint, Glyph};
+use crate::{
+    contour_mapping,
+    problems::{Problem, ProblemDetails},
+    GlyfPoint, Glyph, RunTestsOptions,
+};
 
-const T: f64 = 0.1;
+/// The default smoothness threshold; see [test_kink]
+pub(crate) const DEFAULT_SMOOTHNESS: f64 = 0.1;
 const DEFAULT_KINKINESS_LENGTH: f64 = 0.002;
-const DEFAULT_KINKINESS: f64 = 0.5;
-const DEFAULT_UPEM: u16 = 1000;
+pub(crate) const DEFAULT_KINKINESS: f64 = 0.5;
+pub(crate) const DEFAULT_UPEM: u16 = 1000;
 
-#[allow(clippy::too_many_arguments)]
-pub(crate) fn test_kink<'a>(
-    glyph_a: &'a Glyph,
-    glyph_b: &'a Glyph,
+/// Whether on-curve point `i` of a contour is smooth
+///
+/// A point is smooth if the directions into and out of it are within
+/// `smoothness` (as a sine) of each other and don't double back. Returns
+/// `None` for off-curve points and points which coincide with a neighbour,
+/// which have no direction to compare.
+pub(crate) fn is_smooth(contour: &[GlyfPoint], i: usize, smoothness: f64) -> Option<bool> {
+    let len = contour.len();
+    let pt = contour.get(i)?;
+    if !pt.is_control {
+        return None;
+    }
+    let d_prev = pt.point - contour[(i + len - 1) % len].point;
+    let d_next = contour[(i + 1) % len].point - pt.point;
+    let sin = d_prev.cross(d_next) / (d_prev.length() * d_next.length());
+    if sin.is_nan() {
+        return None;
+    }
+    Some(sin.abs() <= smoothness && d_prev.dot(d_next) >= 0.0)
+}
+
+/// A smooth point which bends at the midway interpolation
+///
+/// Whether it is reported as a kink depends on the tolerance and kinkiness;
+/// see [KinkCandidate::tolerance_at].
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct KinkCandidate {
+    /// The index of the point in the contour
+    pub node: usize,
+    /// The sine of the angle at the point at the midway interpolation
+    sin_mid: f64,
+    /// How far (in font units) the point strays from the line between its neighbours
+    deviation: f64,
+}
+
+impl KinkCandidate {
+    /// The sine of the angle at the point at the midway interpolation
+    pub fn sharpness(&self) -> f64 {
+        self.sin_mid.abs()
+    }
+
+    /// The kink's match ratio, if it is reported at this tolerance and kinkiness
+    pub fn tolerance_at(
+        &self,
+        tolerance: f64,
+        kinkiness: f64,
+        smoothness: f64,
+        upem: u16,
+    ) -> Option<f64> {
+        let deviation_threshold =
+            upem as f64 * DEFAULT_KINKINESS_LENGTH * DEFAULT_KINKINESS / kinkiness;
+        if self.sin_mid.abs() * (tolerance * kinkiness) <= smoothness
+            || self.deviation < deviation_threshold
+        {
+            return None;
+        }
+        Some(smoothness / (self.sin_mid.abs() * kinkiness))
+    }
+}
+
+/// The bend at a point of an interpolated contour
+struct Bend {
+    /// The sine of the angle at the point
+    sin: f64,
+    /// How far (in font units) the point strays from the line between its neighbours
+    deviation: f64,
+    /// The length of the two segments meeting at the point
+    arc_len: f64,
+}
+
+/// The bend at point `i` of the contour interpolated at `t` between two masters
+///
+/// Returns `None` if the point coincides with one of its neighbours.
+fn bend_at(contour0: &[GlyfPoint], contour1: &[GlyfPoint], i: usize, t: f64) -> Option<Bend> {
+    let (len0, len1) = (contour0.len(), contour1.len());
+    if len0 == 0 || len1 == 0 {
+        return None;
+    }
+    let lerp = |j: isize| {
+        let p0 = contour0.get((i as isize + j).rem_euclid(len0 as isize) as usize)?;
+        let p1 = contour1.get((i as isize + j).rem_euclid(len1 as isize) as usize)?;
+        Some(p0.point.lerp(p1.point, t))
+    };
+    let point = lerp(0)?;
+    let d0 = point - lerp(-1)?;
+    let d1 = lerp(1)? - point;
+    let sin = d0.cross(d1) / (d0.length() * d1.length());
+    if sin.is_nan() {
+        return None;
+    }
+    let arc_len = d0.length() + d1.length();
+    Some(Bend {
+        sin,
+        deviation: (d0.cross(d1) / arc_len).abs(),
+        arc_len,
+    })
+}
+
+/// Find the smooth points of a contour which bend at the midway interpolation
+///
+/// These are the points which [test_kink] measures against the tolerance;
+/// finding them doesn't depend on the tolerance, only on `smoothness`.
+pub(crate) fn kink_candidates(
     contour0: &[GlyfPoint],
     contour1: &[GlyfPoint],
-    ix: usize,
-    tolerance: f64,
-    kinkiness: Option<f64>,
-    upem: Option<u16>,
-) -> Vec<Problem> {
-    let kinkiness = kinkiness.unwrap_or(DEFAULT_KINKINESS);
-    let deviation_threshold =
-        upem.unwrap_or(DEFAULT_UPEM) as f64 * DEFAULT_KINKINESS_LENGTH * DEFAULT_KINKINESS
-            / kinkiness;
-    let mut problems = vec![];
+    smoothness: f64,
+) -> Vec<KinkCandidate> {
+    let mut candidates = vec![];
 
     for (i, (pt0, pt1)) in contour0.iter().zip(contour1.iter()).enumerate() {
         if !pt0.is_control || !pt1.is_control {
@@ -33,53 +130,164 @@ pub(crate) fn test_kink<'a>(
         if pt0_prev.is_control && pt1_prev.is_control {
             continue;
         }
+        if is_smooth(contour0, i, smoothness) != Some(true)
+            || is_smooth(contour1, i, smoothness) != Some(true)
+        {
+            continue;
+        }
         let d0_prev = pt0.point - pt0_prev.point;
         let d0_next = pt0_next.point - pt0.point;
         let d1_prev = pt1.point - pt1_prev.point;
         let d1_next = pt1_next.point - pt1.point;
 
-        let sin_0 = d0_prev.cross(d0_next) / (d0_prev.length() * d0_next.length());
-        let sin_1 = d1_prev.cross(d1_next) / (d1_prev.length() * d1_next.length());
-        // No vector, not colinear, not smooth
-        if sin_0.is_nan() || sin_1.is_nan() || sin_0.abs() > T || sin_1.abs() > T {
-            continue;
-        }
-
-        // Check for sharp corners
-        if d0_prev.dot(d0_next) < 0.0 || d1_prev.dot(d1_next) < 0.0 {
-            continue;
-        }
-
         // Are handle ratios similar enough?
         let ratio_0 = d0_prev.length() / (d0_prev.length() + d0_next.length());
         let ratio_1 = d1_prev.length() / (d1_prev.length() + d1_next.length());
-        if (ratio_0 - ratio_1).abs() < T {
+        if (ratio_0 - ratio_1).abs() < smoothness {
             continue;
         }
 
-        let midpoint = pt0.point.lerp(pt1.point, 0.5);
-        let mid_prev = pt0_prev.point.lerp(pt1_prev.point, 0.5);
-        let mid_next = pt0_next.point.lerp(pt1_next.point, 0.5);
-        let mid_d0 = midpoint - mid_prev;
-        let mid_d1 = mid_next - midpoint;
-        let sin_mid = mid_d0.cross(mid_d1) / (mid_d0.length() * mid_d1.length());
-        if sin_mid.is_nan() || sin_mid.abs() * (tolerance * kinkiness) <= T {
+        let Some(Bend {
+            sin: sin_mid,
+            deviation,
+            arc_len,
+        }) = bend_at(contour0, contour1, i, 0.5)
+        else {
+            continue;
+        };
+        if deviation / arc_len > smoothness {
             continue;
         }
 
-        let cross = sin_mid * mid_d0.length() * mid_d1.length();
-        let arc_len = mid_d0.length() + mid_d1.length();
-        let deviation = (cross / arc_len).abs();
-        if deviation < deviation_threshold {
+        candidates.push(KinkCandidate {
+            node: i,
+            sin_mid,
+            deviation,
+        });
+    }
+    candidates
+}
+
+/// Test for kinks introduced at smooth on-curve points during interpolation
+///
+/// `smoothness` (default 0.1) is the threshold used throughout the test: the
+/// maximum sine of the angle at a point for it to count as smooth in each
+/// master, the minimum difference in handle ratios worth investigating, and
+/// the sine of the angle at the midpoint above which a kink is reported.
+/// Smaller values find kinks in gentler curves; larger values reduce noise in
+/// rough display faces. The midpoint angle is scaled by `tolerance * kinkiness`
+/// before being compared with `smoothness`, so raising `kinkiness` reports
+/// more kinks for the same `smoothness`.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn test_kink<'a>(
+    glyph_a: &'a Glyph,
+    glyph_b: &'a Glyph,
+    contour0: &[GlyfPoint],
+    contour1: &[GlyfPoint],
+    ix: usize,
+    tolerance: f64,
+    kinkiness: Option<f64>,
+    smoothness: Option<f64>,
+    upem: Option<u16>,
+) -> Vec<Problem> {
+    let kinkiness = kinkiness.unwrap_or(DEFAULT_KINKINESS);
+    let smoothness = smoothness.unwrap_or(DEFAULT_SMOOTHNESS);
+    let upem = upem.unwrap_or(DEFAULT_UPEM);
+    kink_candidates(contour0, contour1, smoothness)
+        .into_iter()
+        .filter_map(|candidate| {
+            let this_tolerance = candidate.tolerance_at(tolerance, kinkiness, smoothness, upem)?;
+            Some(Problem::kink(
+                glyph_a,
+                glyph_b,
+                ix,
+                candidate.node,
+                this_tolerance,
+            ))
+        })
+        .collect()
+}
+
+/// Drop kinks which don't appear between every pair of masters
+///
+/// `pair_problems` holds the problems found by [crate::run_tests] for each
+/// pair of masters of one glyph. A kink between one pair of masters is often
+/// an acceptable consequence of the design which disappears in the other
+/// interpolations, so this keeps only those kinks (identified by contour and
+/// node) which were found between every pair. Other problems are untouched.
+pub fn retain_persistent_kinks(pair_problems: &mut [Vec<Problem>]) {
+    let kink_at = |problem: &Problem| match problem.details {
+        ProblemDetails::Kink { .. } => Some((problem.contour, problem.node)),
+        _ => None,
+    };
+    let persistent: Vec<(Option<usize>, Option<usize>)> = pair_problems
+        .first()
+        .map(|problems| problems.iter().filter_map(kink_at).collect())
+        .unwrap_or_default();
+    let persistent: Vec<_> = persistent
+        .into_iter()
+        .filter(|kink| {
+            pair_problems
+                .iter()
+                .all(|problems| problems.iter().any(|p| kink_at(p).as_ref() == Some(kink)))
+        })
+        .collect();
+    for problems in pair_problems.iter_mut() {
+        problems.retain(|p| match kink_at(p) {
+            Some(kink) => persistent.contains(&kink),
+            None => true,
+        });
+    }
+}
+
+/// Sample how a kink develops across the interpolation
+///
+/// Returns `samples` evenly spaced `(t, deviation)` pairs from t=0 to t=1,
+/// where the deviation is how far (in font units) the point strays from the
+/// line between its neighbours. This is close to zero at the masters, where
+/// the point is smooth, and peaks somewhere in between.
+pub fn kink_trajectory(
+    contour0: &[GlyfPoint],
+    contour1: &[GlyfPoint],
+    node: usize,
+    samples: usize,
+) -> Vec<(f64, f64)> {
+    let steps = samples.saturating_sub(1).max(1);
+    (0..=steps)
+        .map(|step| step as f64 / steps as f64)
+        .map(|t| {
+            let deviation = bend_at(contour0, contour1, node, t).map_or(0.0, |bend| bend.deviation);
+            (t, deviation)
+        })
+        .collect()
+}
+
+/// Fill in the trajectory of each of the kinks found between two masters
+///
+/// `problems` are the problems found by [crate::run_tests_with] for `glyph_a`
+/// and `glyph_b` with the same `options`; see [kink_trajectory] for the
+/// samples.
+pub fn add_kink_trajectories(
+    glyph_a: &Glyph,
+    glyph_b: &Glyph,
+    problems: &mut [Problem],
+    samples: usize,
+    options: &RunTestsOptions,
+) {
+    let mapping = contour_mapping(glyph_a, glyph_b, options);
+    for problem in problems.iter_mut() {
+        let (Some(contour), Some(node)) = (problem.contour, problem.node) else {
             continue;
-        }
-        let deviation_ratio = deviation / arc_len;
-        if deviation_ratio > T {
+        };
+        let ProblemDetails::Kink { trajectory } = &mut problem.details else {
             continue;
-        }
-
-        let this_tolerance = T / (sin_mid.abs() * kinkiness);
-        problems.push(Problem::kink(glyph_a, glyph_b, ix, i, this_tolerance));
+        };
+        let (Some(contour0), Some(contour1)) = (
+            glyph_a.points.get(contour),
+            mapping.get(contour).and_then(|&jx| glyph_b.points.get(jx)),
+        ) else {
+            continue;
+        };
+        *trajectory = Some(kink_trajectory(contour0, contour1, node, samples));
     }
-    problems
 }
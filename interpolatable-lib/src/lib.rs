@@ -1,27 +1,81 @@
 #![deny(clippy::unwrap_used)]
 #![deny(clippy::expect_used)]
 use core::fmt;
+use std::collections::HashMap;
 
+pub use analysis::{analyze, Analysis};
+pub use basiccompat::test_compatibility;
 pub use bezglyph::BezGlyph;
+pub use calibrate::{calibrate, Tolerances};
+pub use centroid::test_centroid_drift;
+pub use check::{Check, CheckContext};
+pub use consistency::{test_shared_shapes, GlyphPairReport};
+pub use contourorder::{contour_order_costs, explain_contour_order, ContourMatch};
+pub use curvature::test_segment_curvature;
+pub use descriptors::{GlyphDescriptors, IsomorphismSummary};
+pub use extrapolation::test_extrapolation;
+pub use frechet::test_frechet_distance;
 use greencurves::{ComputeControlStatistics, ComputeGreenStatistics, CurveStatistics};
+pub use hausdorff::hausdorff_distance;
+pub use identical::test_identical_master;
+#[cfg(feature = "skrifa")]
+pub use instantiation::{test_instantiation, test_named_instances};
+pub use isomorphism::contour_characteristic_vector;
 use isomorphism::Isomorphisms;
 use itertools::Itertools;
-use kurbo::{BezPath, Point};
-pub use problems::{Problem, ProblemDetails};
+pub use kink::{add_kink_trajectories, kink_trajectory, retain_persistent_kinks};
+use kurbo::{Affine, BezPath, ParamCurve, PathSeg, Point, Rect, Shape, Vec2};
+pub use margin::tolerance_margin;
+pub use metrics::test_metrics;
+pub use options::RunTestsOptions;
+pub use pointorder::test_point_collisions;
+pub use problems::{
+    limit_problems, min_passing_tolerance, retain_confident_start_points, Badness, GlyphStatus,
+    Metric, NavTarget, Problem, ProblemDetails,
+};
+use serde::{Deserialize, Serialize};
+pub use tangent::test_tangent_continuity;
+use translation::contour_movement;
+pub use translation::{translation_offset, DEFAULT_MIN_CONTOUR_MOVEMENT, TRANSLATION_TOLERANCE};
+pub use weight::interpolated_contour_weight;
 
 #[cfg(feature = "skrifa")]
 use skrifa::{prelude::*, setting::VariationSetting};
 
-use startingpoint::test_starting_point;
-use utils::lerp_curve;
+use startingpoint::{proposal_creates_kink, start_point_confidence, test_starting_point};
+use utils::{lerp_curve, lerp_curve_at};
 
+mod analysis;
 mod basiccompat;
 mod bezglyph;
+mod calibrate;
+mod centroid;
+mod check;
+mod consistency;
+mod containment;
 mod contourorder;
+mod curvature;
+mod descriptors;
+mod extrapolation;
+mod extrema;
+mod fillrule;
+mod frechet;
+mod handlelength;
+mod hausdorff;
+mod identical;
+#[cfg(feature = "skrifa")]
+mod instantiation;
 mod isomorphism;
 mod kink;
+mod margin;
+mod metrics;
+mod options;
+mod pointorder;
 mod problems;
+mod selfcrossing;
 mod startingpoint;
+mod tangent;
+mod translation;
 pub mod utils;
 mod weight;
 
@@ -35,7 +89,7 @@ enum NodeType {
     ClosePath,
 }
 
-#[derive(PartialEq, Clone)]
+#[derive(PartialEq, Clone, Serialize, Deserialize)]
 pub struct GlyfPoint {
     pub point: Point,
     pub is_control: bool,
@@ -79,24 +133,275 @@ impl fmt::Debug for GlyfPoint {
 ///
 /// Once you have two glyphs, you can test their interpolability by
 /// passing them to `run_tests`.
-#[derive(Default)]
+///
+/// Glyphs can be serialized to cache them between runs; the raw curve
+/// statistics are not serialized, but the feature vectors derived from
+/// them (which is what the tests use) are.
+#[derive(Default, Serialize, Deserialize)]
 pub struct Glyph {
     pub master_name: String,
     pub master_index: usize,
     // types: Vec<Vec<NodeType>>,
     pub curves: Vec<BezPath>,
+    #[serde(skip)]
     green_stats: Vec<greencurves::GreenStatistics>,
+    #[serde(skip)]
     control_stats: Vec<greencurves::ControlStatistics>,
     green_vectors: Vec<Vec<f64>>,
     control_vectors: Vec<Vec<f64>>,
     pub points: Vec<Vec<GlyfPoint>>,
     isomorphisms: Vec<Isomorphisms>,
+    /// The advance width of the glyph at this location, if known
+    pub advance_width: Option<f64>,
 }
 
 impl Glyph {
     fn new() -> Self {
         Self::default()
     }
+
+    /// The feature vectors computed from the Green's theorem statistics of each contour
+    ///
+    /// Each vector contains the signed square root of the area, the center of
+    /// mass (x, y), twice the standard deviation (x, y) and the correlation
+    /// scaled by the size.
+    pub fn green_vectors(&self) -> &[Vec<f64>] {
+        &self.green_vectors
+    }
+
+    /// The feature vectors computed from the control point statistics of each contour
+    ///
+    /// These have the same layout as [Glyph::green_vectors].
+    pub fn control_vectors(&self) -> &[Vec<f64>] {
+        &self.control_vectors
+    }
+
+    /// The full Green's theorem statistics for a contour
+    ///
+    /// These are not serialized, so this returns `None` for a deserialized glyph.
+    pub fn green_statistics(&self, contour: usize) -> Option<&greencurves::GreenStatistics> {
+        self.green_stats.get(contour)
+    }
+
+    /// The full control point statistics for a contour
+    ///
+    /// These are not serialized, so this returns `None` for a deserialized glyph.
+    pub fn control_statistics(&self, contour: usize) -> Option<&greencurves::ControlStatistics> {
+        self.control_stats.get(contour)
+    }
+
+    /// The perceptual weight of a contour: the square of the size used by the weight checks
+    ///
+    /// This is the absolute area of the contour, as computed from its Green's
+    /// theorem statistics. See [interpolated_contour_weight] for the weight
+    /// part way between two glyphs.
+    pub fn contour_weight(&self, contour: usize) -> Option<f64> {
+        self.green_vectors
+            .get(contour)
+            .and_then(|vector| vector.first())
+            .map(|size| size * size)
+    }
+
+    /// The length of the outline of a contour, in font units
+    ///
+    /// This is the arc length of the contour's curves, accurate to within a
+    /// font unit.
+    pub fn contour_perimeter(&self, contour: usize) -> Option<f64> {
+        self.curves.get(contour).map(|curve| curve.perimeter(1.0))
+    }
+
+    /// The number of points per font unit of a contour's outline
+    ///
+    /// A contour which has had its overlaps removed usually has far fewer
+    /// points along its length than the same contour in another master.
+    /// Returns 0.0 for a contour with no length.
+    pub fn contour_point_density(&self, contour: usize) -> Option<f64> {
+        let perimeter = self.contour_perimeter(contour)?;
+        let num_points = self.points.get(contour)?.len();
+        Some(if perimeter > 0.0 {
+            num_points as f64 / perimeter
+        } else {
+            0.0
+        })
+    }
+
+    /// The turning function of a contour: its cumulative signed turning angle against arc length
+    ///
+    /// The contour's points (on- and off-curve) are treated as a polygon.
+    /// Each element is the distance along the polygon to the start of an
+    /// edge, and the angle (in radians, anticlockwise positive) through which
+    /// the polygon has turned between its first edge and that one; a final
+    /// element at the full length includes the turn back into the first edge,
+    /// so ends at ±2π for a simple contour. As angles are measured from the
+    /// first edge, this doesn't change when the contour is rotated, moved or
+    /// scaled (other than the lengths). Points which coincide with the previous
+    /// point are skipped. Returns an empty list if the contour doesn't exist or
+    /// has fewer than two distinct points.
+    pub fn contour_turning_function(&self, contour: usize) -> Vec<(f64, f64)> {
+        let Some(points) = self.points.get(contour) else {
+            return vec![];
+        };
+        let len = points.len();
+        let edges: Vec<Vec2> = (0..len)
+            .map(|i| points[(i + 1) % len].point - points[i].point)
+            .filter(|edge| edge.hypot2() > 0.0)
+            .collect();
+        let Some(first) = edges.first() else {
+            return vec![];
+        };
+        let mut result = Vec::with_capacity(edges.len() + 1);
+        let (mut arc_length, mut angle) = (0.0, 0.0);
+        let mut previous = *first;
+        for edge in edges.iter().chain(std::iter::once(first)) {
+            angle += previous.cross(*edge).atan2(previous.dot(*edge));
+            result.push((arc_length, angle));
+            arc_length += edge.hypot();
+            previous = *edge;
+        }
+        result
+    }
+
+    /// The precomputed features of this glyph, for persisting or indexing
+    pub fn descriptors(&self) -> GlyphDescriptors {
+        self.into()
+    }
+
+    /// Interpolate between this glyph and another, producing a new glyph at `t`
+    ///
+    /// `t` is 0.0 at this glyph and 1.0 at `other`. The resulting glyph has its
+    /// statistics and isomorphisms computed afresh, so it can itself be tested
+    /// against other glyphs. Returns `None` if the glyphs are not structurally
+    /// compatible.
+    pub fn interpolated(&self, other: &Glyph, t: f64) -> Option<Glyph> {
+        if self.curves.len() != other.curves.len() {
+            return None;
+        }
+        let curves = self
+            .curves
+            .iter()
+            .zip(other.curves.iter())
+            .map(|(c0, c1)| lerp_curve_at(c0, c1, t))
+            .collect::<Option<Vec<BezPath>>>()?;
+        let mut glyph: Glyph = BezGlyph::new_from_paths(curves).into();
+        glyph.master_name = format!("{}..{}@{}", self.master_name, other.master_name, t);
+        glyph.master_index = self.master_index;
+        glyph.advance_width = self
+            .advance_width
+            .zip(other.advance_width)
+            .map(|(a, b)| a + (b - a) * t);
+        Some(glyph)
+    }
+
+    /// Apply an affine transformation to the glyph's outline, producing a new glyph
+    ///
+    /// The statistics and isomorphisms are computed afresh. If the
+    /// transformation is a reflection, the contours are reversed, so that
+    /// they keep their winding direction and the result can be tested
+    /// against this glyph without every contour being reported as reversed.
+    pub fn transformed(&self, affine: Affine) -> Glyph {
+        let reflection = affine.determinant() < 0.0;
+        let curves = self
+            .curves
+            .iter()
+            .map(|curve| {
+                let curve = affine * curve.clone();
+                if reflection {
+                    reverse_contour(&curve)
+                } else {
+                    curve
+                }
+            })
+            .collect();
+        let mut glyph: Glyph = BezGlyph::new_from_paths(curves).into();
+        glyph.master_name = self.master_name.clone();
+        glyph.master_index = self.master_index;
+        glyph.advance_width = self.advance_width;
+        glyph
+    }
+
+    /// Reflect the glyph's outline about the center of its bounding box
+    ///
+    /// This is for checking the symmetry of glyphs which are designed to be
+    /// symmetric, such as A, H and O: run [run_tests] on the glyph and its
+    /// mirror image (`glyph.mirrored(Axis::X)` for left-right symmetry), and
+    /// any problems show where the symmetry breaks, by contour and node.
+    /// Unless a contour's start point is on the axis of symmetry, it won't be
+    /// in the same place in the mirror image, so `WrongStartPoint` problems
+    /// are to be expected and can be ignored; the other problems are real
+    /// asymmetries.
+    pub fn mirrored(&self, axis: Axis) -> Glyph {
+        let center = self.bounds().map(|bbox| bbox.center()).unwrap_or_default();
+        let reflection = match axis {
+            Axis::X => Affine::new([-1.0, 0.0, 0.0, 1.0, 2.0 * center.x, 0.0]),
+            Axis::Y => Affine::new([1.0, 0.0, 0.0, -1.0, 0.0, 2.0 * center.y]),
+        };
+        self.transformed(reflection)
+    }
+
+    /// The bounding box of the glyph's outline, or `None` if it has no contours
+    pub fn bounds(&self) -> Option<Rect> {
+        union_bounds(self.curves.iter())
+    }
+
+    /// The bounding box of the midpoint interpolation between this glyph and another
+    ///
+    /// Each contour is interpolated point by point with the contour in the
+    /// same position in `other`, as it is drawn in the "midway" panels of the
+    /// reports. Returns `None` if the glyphs have no contours or any pair of
+    /// contours is not structurally compatible.
+    pub fn midpoint_bounds(&self, other: &Glyph) -> Option<Rect> {
+        if self.curves.len() != other.curves.len() {
+            return None;
+        }
+        let midpoints = self
+            .curves
+            .iter()
+            .zip(other.curves.iter())
+            .map(|(c0, c1)| lerp_curve(c0, c1))
+            .collect::<Option<Vec<_>>>()?;
+        union_bounds(midpoints.iter())
+    }
+
+    /// The left sidebearing of the glyph, i.e. the minimum x coordinate of its outline
+    pub fn lsb(&self) -> Option<f64> {
+        self.curves
+            .iter()
+            .map(|c| c.bounding_box().min_x())
+            .reduce(f64::min)
+    }
+}
+
+/// The union of the bounding boxes of some contours
+pub(crate) fn union_bounds<'a>(curves: impl Iterator<Item = &'a BezPath>) -> Option<Rect> {
+    curves.map(|c| c.bounding_box()).reduce(|a, b| a.union(b))
+}
+
+/// The direction in which [Glyph::mirrored] reflects a glyph
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Axis {
+    /// Reflect the x coordinates, swapping left and right
+    X,
+    /// Reflect the y coordinates, swapping top and bottom
+    Y,
+}
+
+/// Reverse the direction of a contour, keeping its start point
+fn reverse_contour(contour: &BezPath) -> BezPath {
+    let segments: Vec<PathSeg> = contour.segments().collect();
+    let mut reversed = BezPath::new();
+    let Some(last) = segments.last() else {
+        return contour.clone();
+    };
+    reversed.move_to(last.end());
+    for segment in segments.iter().rev() {
+        match segment.reverse() {
+            PathSeg::Line(line) => reversed.line_to(line.p1),
+            PathSeg::Quad(quad) => reversed.quad_to(quad.p1, quad.p2),
+            PathSeg::Cubic(cubic) => reversed.curve_to(cubic.p1, cubic.p2, cubic.p3),
+        }
+    }
+    reversed.close_path();
+    reversed
 }
 
 fn stats_to_vectors(stats: &dyn CurveStatistics) -> Vec<f64> {
@@ -114,59 +419,129 @@ fn stats_to_vectors(stats: &dyn CurveStatistics) -> Vec<f64> {
     ]
 }
 
+/// Which statistics drive the start point and weight checks
+///
+/// The contour order check always uses both. The Green's theorem statistics
+/// (the default) are computed from the curves themselves, so they measure the
+/// true area and center of mass. The control polygon statistics treat the
+/// off-curve points as if they were on the outline; they are only an
+/// approximation of the shape, but follow the points the designer actually
+/// edits, and can match better for some styles (for example, where handles
+/// are long compared with the curves they shape).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Statistics {
+    #[default]
+    Green,
+    Control,
+}
+
+/// Build a glyph directly from its contours, one [BezPath] per contour
+impl From<Vec<BezPath>> for Glyph {
+    fn from(paths: Vec<BezPath>) -> Self {
+        BezGlyph::new_from_paths(paths).into()
+    }
+}
+
 impl From<BezGlyph> for Glyph {
     fn from(val: BezGlyph) -> Self {
         let mut glyph = Glyph::new();
-        for path in val.0 {
+        for path in val.into_rounded_paths() {
             let green_stats = path.green_statistics();
             let control_stats = path.control_statistics();
-            glyph.green_vectors.push(stats_to_vectors(&green_stats));
-            glyph.control_vectors.push(stats_to_vectors(&control_stats));
-            glyph.green_stats.push(green_stats);
-            glyph.control_stats.push(control_stats);
-            let mut points = vec![];
-            let mut types = vec![];
-            for el in path.iter() {
-                match el {
-                    kurbo::PathEl::MoveTo(p) => {
-                        points.push(GlyfPoint::oncurve(p));
-                        types.push(NodeType::MoveTo);
-                    }
-                    kurbo::PathEl::LineTo(p) => {
-                        points.push(GlyfPoint::oncurve(p));
-                        types.push(NodeType::LineTo);
-                    }
-                    kurbo::PathEl::QuadTo(p0, p1) => {
-                        points.push(GlyfPoint::offcurve(p0));
-                        types.push(NodeType::OffCurve);
-                        points.push(GlyfPoint {
-                            point: p1,
-                            is_control: true,
-                        });
-                        types.push(NodeType::QuadTo);
-                    }
-                    kurbo::PathEl::CurveTo(p0, p1, p2) => {
-                        points.push(GlyfPoint::offcurve(p0));
-                        types.push(NodeType::OffCurve);
-                        points.push(GlyfPoint::offcurve(p1));
-                        types.push(NodeType::OffCurve);
-                        points.push(GlyfPoint {
-                            point: p2,
-                            is_control: true,
-                        });
-                        types.push(NodeType::CurveTo);
-                    }
-                    kurbo::PathEl::ClosePath => {
-                        types.push(NodeType::ClosePath);
-                    }
+            glyph.push_contour(path, green_stats, control_stats);
+        }
+        glyph
+    }
+}
+
+impl Glyph {
+    /// Build a glyph using statistics which have already been computed
+    ///
+    /// This is the same as converting the [BezGlyph] into a glyph, but the
+    /// Green's theorem and control point statistics of each contour are taken
+    /// from `green_stats` and `control_stats` rather than computed again;
+    /// only the points and isomorphisms are computed. The statistics are
+    /// trusted, so they must have been computed from the same paths (after
+    /// any rounding to the glyph's grid). Returns `None` if there isn't one
+    /// of each kind of statistics per path.
+    pub fn from_bezglyph_with_stats(
+        bezglyph: BezGlyph,
+        green_stats: Vec<greencurves::GreenStatistics>,
+        control_stats: Vec<greencurves::ControlStatistics>,
+    ) -> Option<Self> {
+        let paths = bezglyph.into_rounded_paths();
+        if green_stats.len() != paths.len() || control_stats.len() != paths.len() {
+            return None;
+        }
+        let mut glyph = Glyph::new();
+        for ((path, green_stats), control_stats) in
+            paths.into_iter().zip(green_stats).zip(control_stats)
+        {
+            glyph.push_contour(path, green_stats, control_stats);
+        }
+        Some(glyph)
+    }
+
+    fn push_contour(
+        &mut self,
+        path: BezPath,
+        green_stats: greencurves::GreenStatistics,
+        control_stats: greencurves::ControlStatistics,
+    ) {
+        self.green_vectors.push(stats_to_vectors(&green_stats));
+        self.control_vectors.push(stats_to_vectors(&control_stats));
+        self.green_stats.push(green_stats);
+        self.control_stats.push(control_stats);
+        let mut points = vec![];
+        let mut types = vec![];
+        for el in path.iter() {
+            match el {
+                kurbo::PathEl::MoveTo(p) => {
+                    points.push(GlyfPoint::oncurve(p));
+                    types.push(NodeType::MoveTo);
+                }
+                kurbo::PathEl::LineTo(p) => {
+                    points.push(GlyfPoint::oncurve(p));
+                    types.push(NodeType::LineTo);
+                }
+                kurbo::PathEl::QuadTo(p0, p1) => {
+                    points.push(GlyfPoint::offcurve(p0));
+                    types.push(NodeType::OffCurve);
+                    points.push(GlyfPoint {
+                        point: p1,
+                        is_control: true,
+                    });
+                    types.push(NodeType::QuadTo);
+                }
+                kurbo::PathEl::CurveTo(p0, p1, p2) => {
+                    points.push(GlyfPoint::offcurve(p0));
+                    types.push(NodeType::OffCurve);
+                    points.push(GlyfPoint::offcurve(p1));
+                    types.push(NodeType::OffCurve);
+                    points.push(GlyfPoint {
+                        point: p2,
+                        is_control: true,
+                    });
+                    types.push(NodeType::CurveTo);
+                }
+                kurbo::PathEl::ClosePath => {
+                    types.push(NodeType::ClosePath);
                 }
             }
+        }
 
-            glyph.isomorphisms.push(Isomorphisms::new(&points));
-            glyph.points.push(points);
-            glyph.curves.push(path);
+        // A contour which returns to its start point explicitly has the start
+        // point twice; drop the second copy, so that it has the same points
+        // (and isomorphisms) as a contour which closes implicitly
+        if points.len() > 1
+            && points.first() == points.last()
+            && points.first().is_some_and(|pt| pt.is_control)
+        {
+            points.pop();
         }
-        glyph
+        self.isomorphisms.push(Isomorphisms::new(&points));
+        self.points.push(points);
+        self.curves.push(path);
     }
 }
 
@@ -177,22 +552,69 @@ impl Glyph {
         glyph_id: GlyphId,
         location: &[VariationSetting],
     ) -> Option<Self> {
-        let collection = font.outline_glyphs();
+        Self::new_from_font_on_grid(font, glyph_id, location, None)
+    }
+
+    /// Load a glyph from a font, rounding its coordinates to multiples of `grid`
+    ///
+    /// See [BezGlyph::set_grid]. With a grid of `None`, this is the same as
+    /// [Glyph::new_from_font].
+    pub fn new_from_font_on_grid(
+        font: &FontRef,
+        glyph_id: GlyphId,
+        location: &[VariationSetting],
+        grid: Option<f64>,
+    ) -> Option<Self> {
         let loc = font.axes().location(location);
+        let mut glyph = Self::new_from_font_at(font, glyph_id, &loc, grid)?;
+        glyph.master_name = location
+            .iter()
+            .map(|x| format!("{}={}", x.selector, x.value))
+            .join(" ");
+        Some(glyph)
+    }
+
+    /// Load a glyph from a font at a normalized location
+    pub(crate) fn new_from_font_at(
+        font: &FontRef,
+        glyph_id: GlyphId,
+        loc: &skrifa::instance::Location,
+        grid: Option<f64>,
+    ) -> Option<Self> {
+        let collection = font.outline_glyphs();
         let outlined = collection.get(glyph_id)?;
         let settings =
-            skrifa::outline::DrawSettings::unhinted(skrifa::prelude::Size::unscaled(), &loc);
+            skrifa::outline::DrawSettings::unhinted(skrifa::prelude::Size::unscaled(), loc);
         let mut bezglyph = BezGlyph::default();
         outlined.draw(settings, &mut bezglyph).ok()?;
+        bezglyph.set_grid(grid);
         let mut glyph: Glyph = bezglyph.into();
-        glyph.master_name = location
-            .iter()
-            .map(|x| format!("{}={}", x.selector, x.value))
-            .join(" ");
+        glyph.advance_width = font
+            .glyph_metrics(Size::unscaled(), loc)
+            .advance_width(glyph_id)
+            .map(|x| x as f64);
         Some(glyph)
     }
 }
 
+/// Find which contour of `glyph_b` corresponds to each contour of `glyph_a`
+///
+/// This is the contour mapping which [run_tests_with] uses internally, given
+/// the same `options`: element `i` of the result is the index of the contour
+/// in `glyph_b` which is tested against contour `i` of `glyph_a`. If the
+/// contours are already in a consistent order, the glyphs have different
+/// numbers of contours, or `options.ignore_contour_order` is set, this is the
+/// identity mapping.
+pub fn contour_mapping(glyph_a: &Glyph, glyph_b: &Glyph, options: &RunTestsOptions) -> Vec<usize> {
+    let identity = || (0..glyph_a.curves.len()).collect();
+    if options.ignore_contour_order || glyph_a.curves.len() != glyph_b.curves.len() {
+        return identity();
+    }
+    let (_, matching) =
+        contourorder::test_contour_order(glyph_a, glyph_b, options.max_contour_distance);
+    matching.map(|m| m.mapping()).unwrap_or_else(identity)
+}
+
 /// The main interpolatability testing function
 ///
 /// Returns a list of [Problem]s, which are serializable and can be
@@ -202,9 +624,13 @@ impl Glyph {
 ///
 /// * `glyph_a` - the first glyph to test
 /// * `glyph_b` - the second glyph to test
-/// * `tolerance` - the maximum tolerance for problems; defaults to 0.95
+/// * `tolerance` - the match ratio (1.0 being a perfect match) below which
+///   problems are reported; defaults to 0.95. See [Badness] for how this
+///   relates to the badness of the reported problems.
 /// * `kinkiness` - the maximum tolerance for kinks; defaults to 0.5
 /// * `upem` - the UPEM value; defaults to 1000
+///
+/// For the other settings, use [run_tests_with] and [RunTestsOptions].
 pub fn run_tests<'a>(
     glyph_a: &'a Glyph,
     glyph_b: &'a Glyph,
@@ -212,7 +638,95 @@ pub fn run_tests<'a>(
     kinkiness: Option<f64>,
     upem: Option<u16>,
 ) -> Vec<Problem> {
+    let options = RunTestsOptions {
+        tolerance,
+        kinkiness,
+        upem,
+        ..Default::default()
+    };
+    run_tests_with(glyph_a, glyph_b, &options)
+}
+
+/// Run the interpolatability tests, overriding the tolerance for some contours
+///
+/// This is the same as [run_tests], except that `contour_tolerances` maps
+/// contour indices (in `glyph_a`) to tolerances which replace the global
+/// `tolerance` in the start point, weight and kink checks for those contours.
+/// Contours which are not listed use the global tolerance. It is a shorthand
+/// for [run_tests_with] with [RunTestsOptions::contour_tolerances] set.
+pub fn run_tests_with_contour_tolerances<'a>(
+    glyph_a: &'a Glyph,
+    glyph_b: &'a Glyph,
+    contour_tolerances: &HashMap<usize, f64>,
+    tolerance: Option<f64>,
+    kinkiness: Option<f64>,
+    upem: Option<u16>,
+) -> Vec<Problem> {
+    let options = RunTestsOptions {
+        tolerance,
+        kinkiness,
+        upem,
+        contour_tolerances: contour_tolerances.clone(),
+        ..Default::default()
+    };
+    run_tests_with(glyph_a, glyph_b, &options)
+}
+
+/// Run the interpolatability tests with the given [RunTestsOptions]
+///
+/// Returns a list of [Problem]s, which are serializable and can be
+/// converted to JSON.
+pub fn run_tests_with(glyph_a: &Glyph, glyph_b: &Glyph, options: &RunTestsOptions) -> Vec<Problem> {
+    run_tests_inner(glyph_a, glyph_b, options, false)
+}
+
+/// Does this problem mean the glyphs fail to interpolate?
+///
+/// Every problem does, unless it is only advisory (see [Problem::is_advisory]).
+fn fails_interpolation(problem: &Problem) -> bool {
+    !problem.is_advisory()
+}
+
+/// Quickly determine whether two glyphs interpolate
+///
+/// This runs the same checks as [run_tests_with], but stops as soon as a
+/// failing problem is found, so it is cheaper when all you need is a
+/// pass/fail gate. Advisory problems (see [Problem::is_advisory]) don't
+/// count as failures.
+pub fn is_interpolatable(glyph_a: &Glyph, glyph_b: &Glyph, options: &RunTestsOptions) -> bool {
+    !run_tests_inner(glyph_a, glyph_b, options, true)
+        .iter()
+        .any(fails_interpolation)
+}
+
+/// The body of [run_tests]; if `stop_at_first` is set, return as soon as
+/// a problem which fails the glyphs (see [fails_interpolation]) is found
+fn run_tests_inner<'a>(
+    glyph_a: &'a Glyph,
+    glyph_b: &'a Glyph,
+    options: &RunTestsOptions,
+    stop_at_first: bool,
+) -> Vec<Problem> {
+    let RunTestsOptions {
+        tolerance,
+        kinkiness,
+        smoothness,
+        upem,
+        max_contour_distance,
+        min_contour_movement,
+        ref weight_samples,
+        start_point_leeway,
+        ignore_contour_order,
+        statistics,
+        ref contour_tolerances,
+        check_extrema,
+        check_handle_length,
+        check_start_point_type,
+        ref custom_checks,
+    } = *options;
+    let weight_samples = weight_samples.as_deref();
     let tolerance = tolerance.unwrap_or(0.95);
+    let min_contour_movement = min_contour_movement.unwrap_or(DEFAULT_MIN_CONTOUR_MOVEMENT);
     let mut problems = vec![];
 
     problems.extend(basiccompat::test_compatibility(glyph_a, glyph_b));
@@ -221,20 +735,40 @@ pub fn run_tests<'a>(
         return problems;
     }
 
-    let (contour_tolerance, matching) = contourorder::test_contour_order(glyph_a, glyph_b);
+    let (contour_tolerance, matching) = if ignore_contour_order {
+        (1.0, None)
+    } else {
+        contourorder::test_contour_order(glyph_a, glyph_b, max_contour_distance)
+    };
     if let Some(matching) = matching.as_ref() {
         if contour_tolerance < tolerance {
             problems.push(Problem::contour_order(
                 glyph_a,
                 glyph_b,
-                tolerance,
+                contour_tolerance,
                 (0..matching.len()).collect::<Vec<usize>>(),
                 matching.iter().map(|x| x.column).collect(),
+                // The areas' signs differ if the contour is wound the other way round
+                matching
+                    .iter()
+                    .filter(|pos| {
+                        (glyph_a.green_vectors[pos.row][0] < 0.0)
+                            != (glyph_b.green_vectors[pos.column][0] < 0.0)
+                    })
+                    .map(|pos| pos.row)
+                    .collect(),
             ));
         }
     }
+    if stop_at_first && problems.iter().any(fails_interpolation) {
+        return problems;
+    }
     let m0_isomorphisms = &glyph_a.isomorphisms;
-    let m0_vectors = &glyph_a.green_vectors;
+    let vectors = |glyph: &'a Glyph| match statistics {
+        Statistics::Green => &glyph.green_vectors,
+        Statistics::Control => &glyph.control_vectors,
+    };
+    let m0_vectors = vectors(glyph_a);
     let m0_curves = &glyph_a.curves;
     let m0_points = &glyph_a.points;
 
@@ -242,14 +776,14 @@ pub fn run_tests<'a>(
         if let Some(matching) = matching.as_ref() {
             (
                 &matching.reorder(&glyph_b.isomorphisms),
-                &matching.reorder(&glyph_b.green_vectors),
+                &matching.reorder(vectors(glyph_b)),
                 &matching.reorder(&glyph_b.curves),
                 &matching.reorder(&glyph_b.points),
             )
         } else {
             (
                 &glyph_b.isomorphisms,
-                &glyph_b.green_vectors,
+                vectors(glyph_b),
                 &glyph_b.curves,
                 &glyph_b.points,
             )
@@ -259,41 +793,173 @@ pub fn run_tests<'a>(
         .zip(m1_curves.iter())
         .map(|(c0, c1)| lerp_curve(c0, c1))
         .collect();
+    problems.extend(containment::test_containment(
+        glyph_a,
+        glyph_b,
+        m0_curves,
+        m1_curves,
+        &midpoint_interpolations,
+    ));
+    problems.extend(fillrule::test_fill_rule(
+        glyph_a,
+        glyph_b,
+        m0_curves,
+        m1_curves,
+        &midpoint_interpolations,
+    ));
 
     for (ix, (contour_0, contour_1)) in m0_isomorphisms
         .iter()
         .zip(m1_isomorphisms.iter())
         .enumerate()
     {
-        if contour_0.len() == 0 || contour_1.len() != contour_1.len() {
+        if stop_at_first && problems.iter().any(fails_interpolation) {
+            return problems;
+        }
+        let tolerance = contour_tolerances.get(&ix).copied().unwrap_or(tolerance);
+        if contour_0.len() == 0 || contour_1.len() == 0 {
+            problems.push(Problem::unchecked_contour(
+                glyph_a,
+                glyph_b,
+                ix,
+                "contour has no points",
+            ));
+            continue;
+        }
+        if translation_offset(&m0_points[ix], &m1_points[ix]).is_some() {
+            // Clean (translated): the contour interpolates perfectly
+            continue;
+        }
+        if contour_movement(&m0_points[ix], &m1_points[ix]) < min_contour_movement {
+            // Barely changed: anything we found would be noise
+            continue;
+        }
+        if selfcrossing::area_cancels_out(&m0_curves[ix])
+            || selfcrossing::area_cancels_out(&m1_curves[ix])
+        {
+            problems.push(Problem::unchecked_contour(
+                glyph_a,
+                glyph_b,
+                ix,
+                "contour crosses itself so that its area cancels out, so its start point and weight were not checked",
+            ));
             continue;
         }
-        if let Some((this_tolerance, proposed_point, reverse)) = test_starting_point(
-            glyph_b, contour_0, contour_1, m0_vectors, m1_vectors, ix, tolerance,
-        ) {
+        let has_oncurve = |points: &[GlyfPoint]| points.iter().any(|pt| pt.is_control);
+        if !has_oncurve(&m0_points[ix]) || !has_oncurve(&m1_points[ix]) {
+            // Start points are only meaningful for on-curve points
+            problems.push(Problem::unchecked_contour(
+                glyph_a,
+                glyph_b,
+                ix,
+                "contour has no on-curve points, so its start point was not checked",
+            ));
+        } else if let Some((this_tolerance, proposed_point, reverse, rotation_delta)) =
+            test_starting_point(
+                glyph_b,
+                contour_0,
+                contour_1,
+                m0_vectors,
+                m1_vectors,
+                ix,
+                start_point_leeway,
+            )
+        {
             if this_tolerance < tolerance {
-                problems.push(Problem::wrong_start_point(
+                // The areas' signs differ if the contour is wound the other way round
+                let opposite_winding = (m0_vectors[ix][0] < 0.0) != (m1_vectors[ix][0] < 0.0);
+                if reverse && opposite_winding {
+                    problems.push(Problem::contour_direction(
+                        glyph_a,
+                        glyph_b,
+                        this_tolerance,
+                        ix,
+                    ));
+                } else {
+                    problems.push(Problem::wrong_start_point(
+                        glyph_a,
+                        glyph_b,
+                        this_tolerance,
+                        ix,
+                        ProblemDetails::WrongStartPoint {
+                            proposed_point,
+                            reverse,
+                            rotation_delta,
+                            confidence: start_point_confidence(&m0_vectors[ix], &m1_vectors[ix]),
+                            creates_kink: contour_1.get(proposed_point).is_some_and(|proposal| {
+                                proposal_creates_kink(
+                                    &m0_points[ix],
+                                    &m1_points[ix],
+                                    proposal,
+                                    smoothness.unwrap_or(kink::DEFAULT_SMOOTHNESS),
+                                )
+                            }),
+                        },
+                    ));
+                }
+            }
+        }
+        for &t in weight_samples.unwrap_or(&[0.5]) {
+            let interpolation = if t == 0.5 {
+                midpoint_interpolations.get(ix).cloned().flatten()
+            } else {
+                lerp_curve_at(&m0_curves[ix], &m1_curves[ix], t)
+            };
+            if let Some(mid) = interpolation {
+                problems.extend(weight::test_over_underweight(
                     glyph_a,
                     glyph_b,
-                    this_tolerance,
+                    &m0_vectors[ix],
+                    &m1_vectors[ix],
+                    &mid,
+                    t,
+                    tolerance,
                     ix,
-                    proposed_point,
-                    reverse,
+                    upem.unwrap_or(kink::DEFAULT_UPEM),
+                    statistics,
                 ));
             }
         }
-        if let Some(Some(mid)) = midpoint_interpolations.get(ix) {
-            problems.extend(weight::test_over_underweight(
+
+        if check_extrema {
+            problems.extend(extrema::test_extrema(
+                glyph_a,
+                glyph_b,
+                &m0_curves[ix],
+                &m1_curves[ix],
+                ix,
+            ));
+        }
+
+        if check_start_point_type {
+            problems.extend(startingpoint::test_start_point_type(
+                glyph_a,
+                glyph_b,
+                &m0_points[ix],
+                &m1_points[ix],
+                ix,
+                smoothness,
+            ));
+        }
+
+        if check_handle_length {
+            problems.extend(handlelength::test_handle_length(
                 glyph_a,
                 glyph_b,
-                &m0_vectors[ix],
-                &m1_vectors[ix],
-                mid,
-                tolerance,
+                &m0_points[ix],
+                &m1_points[ix],
                 ix,
             ));
         }
 
+        problems.extend(pointorder::test_point_ordering(
+            glyph_a,
+            glyph_b,
+            &m0_points[ix],
+            &m1_points[ix],
+            ix,
+        ));
+
         problems.extend(kink::test_kink(
             glyph_a,
             glyph_b,
@@ -302,9 +968,23 @@ pub fn run_tests<'a>(
             ix,
             tolerance,
             kinkiness,
+            smoothness,
             upem,
         ));
     }
+    if stop_at_first && problems.iter().any(fails_interpolation) {
+        return problems;
+    }
+
+    let ctx = CheckContext {
+        tolerance,
+        kinkiness,
+        upem,
+        matching: matching.as_ref(),
+    };
+    for check in custom_checks {
+        problems.extend(check.run(glyph_a, glyph_b, &ctx));
+    }
 
     problems
 }
@@ -319,6 +999,22 @@ mod tests {
 
     use super::*;
 
+    /// A closed contour through `points`
+    fn closed_path(points: &[(f64, f64)]) -> BezPath {
+        let mut path = BezPath::new();
+        path.move_to(points[0]);
+        for &point in &points[1..] {
+            path.line_to(point);
+        }
+        path.close_path();
+        path
+    }
+
+    /// A glyph with a single closed contour through `points`
+    fn polygon(points: &[(f64, f64)]) -> Glyph {
+        BezGlyph::new_from_paths(vec![closed_path(points)]).into()
+    }
+
     #[test]
     fn test_stuff() {
         let fontdata = include_bytes!("../NotoSerif-Italic.ttf");
@@ -348,7 +1044,7 @@ mod tests {
         let glyph1 = Glyph::new_from_font(&font, glyph_id, &[]).expect("Fail");
         let glyph2 =
             Glyph::new_from_font(&font, glyph_id, &[("wght", 800.0).into()]).expect("Fail");
-        let problems = run_tests(&glyph1, &glyph2, None, None, None);
+        let problems = run_tests_with(&glyph1, &glyph2, &RunTestsOptions::default());
         assert_eq!(problems.len(), 1);
         let problem = serde_json::to_value(&problems[0]).unwrap();
         let problem = problem.as_object().unwrap();
@@ -389,4 +1085,594 @@ mod tests {
         assert_eq!(last.rotated_list.len(), 108);
         assert_eq!(last.rotation, 18);
     }
+
+    #[test]
+    fn test_mixed_quadratic_and_cubic() {
+        // Cubic glyf outlines can mix quadratic and cubic segments in one
+        // contour; glyph 1 of this font is a quadratic curve followed by a
+        // cubic one
+        let fontdata = include_bytes!("../CubicTest.ttf");
+        let font = FontRef::new(fontdata).expect("Can't parse font");
+        let glyph = Glyph::new_from_font(&font, GlyphId::new(1), &[]).expect("Fail");
+        assert_eq!(
+            glyph.points[0]
+                .iter()
+                .map(|p| p.is_control)
+                .collect::<Vec<_>>(),
+            vec![true, false, true, false, false]
+        );
+        // The on/off-curve pattern isn't periodic, so there is exactly one
+        // forward and one reversed isomorphism
+        assert_eq!(glyph.isomorphisms[0].len(), 2);
+        let characteristics = glyph.isomorphisms[0].iter().collect::<Vec<_>>();
+        assert!(!characteristics[0].reverse);
+        assert_eq!(characteristics[0].rotation, 0);
+        assert!(characteristics[1].reverse);
+
+        // Testing the glyph against itself finds nothing
+        let problems = run_tests_with(&glyph, &glyph, &RunTestsOptions::default());
+        assert!(problems.is_empty());
+    }
+
+    #[test]
+    fn test_implied_oncurve_points() {
+        // The same contour, with its on-curve point between two off-curves
+        // implied in one master and explicit in the other
+        let mut implied = BezGlyph::default();
+        implied.next().move_to((0.0, 0.0));
+        implied.qcurve_to(&[(0.0, 100.0), (100.0, 100.0), (100.0, 0.0)]);
+        implied.current().close_path();
+        let mut explicit = BezGlyph::default();
+        explicit.next().move_to((0.0, 0.0));
+        explicit.qcurve_to(&[(0.0, 100.0), (50.0, 100.0)]);
+        explicit.qcurve_to(&[(100.0, 100.0), (100.0, 0.0)]);
+        explicit.current().close_path();
+        let implied: Glyph = implied.into();
+        let explicit: Glyph = explicit.into();
+
+        assert_eq!(implied.points[0].len(), explicit.points[0].len());
+        assert!(test_compatibility(&implied, &explicit).is_empty());
+        assert!(run_tests_with(&implied, &explicit, &RunTestsOptions::default()).is_empty());
+    }
+
+    #[test]
+    fn test_isomorphism_rotation() {
+        let mut path = BezPath::new();
+        path.move_to((0.0, 0.0));
+        path.line_to((100.0, 0.0));
+        path.curve_to((150.0, 0.0), (200.0, 50.0), (200.0, 100.0));
+        path.line_to((0.0, 100.0));
+        path.close_path();
+        let glyph: Glyph = BezGlyph::new_from_paths(vec![path]).into();
+        let points = &glyph.points[0];
+        assert_eq!(points.len(), 6);
+        let characteristics = glyph.isomorphisms[0].iter().collect::<Vec<_>>();
+        assert!(characteristics.iter().any(|c| c.reverse));
+        // Each characteristic vector starts with the position of its start
+        // point, which must be the point its rotation refers to, whether or
+        // not the contour was reversed
+        for characteristic in characteristics.iter() {
+            assert_eq!(
+                characteristic.rotated_list[0],
+                points[characteristic.rotation].point.to_vec2(),
+                "rotation {} (reverse: {})",
+                characteristic.rotation,
+                characteristic.reverse
+            );
+        }
+        // Reversed, the contour visits the points in the opposite order from
+        // its start point
+        for characteristic in characteristics.iter().filter(|c| c.reverse) {
+            let previous = (characteristic.rotation + points.len() - 1) % points.len();
+            assert_eq!(
+                characteristic.rotated_list[4],
+                points[previous].point.to_vec2()
+            );
+        }
+    }
+
+    #[test]
+    fn test_instantiation_divergence() {
+        // Glyph 1's horizontal deltas include an intermediate region peaking
+        // at the midpoint of the weight axis, so the CFF2 instance there is
+        // 100 units wider than the interpolated outline
+        let fontdata = include_bytes!("../variable_otf/BlendTest-VF.otf");
+        let font = FontRef::new(fontdata).expect("Can't parse font");
+        let light = [("wght", 100.0).into()];
+        let bold = [("wght", 900.0).into()];
+        assert!(test_instantiation(&font, GlyphId::new(0), &light, &bold).is_empty());
+        let problems = test_instantiation(&font, GlyphId::new(1), &light, &bold);
+        assert_eq!(problems.len(), 1);
+        let ProblemDetails::InstantiationDivergence { distance } = problems[0].details else {
+            panic!("Expected an instantiation divergence");
+        };
+        assert!((distance - 100.0).abs() < 1.0);
+    }
+
+    #[test]
+    fn test_analysis_matches_run_tests() {
+        let fontdata = include_bytes!("../variable_ttf/TwisterTest-VF.ttf");
+        let font = FontRef::new(fontdata).expect("Can't parse font");
+        for glyph_id in ['A', 'B', 'O']
+            .iter()
+            .filter_map(|&c| font.charmap().map(c))
+        {
+            let glyph1 = Glyph::new_from_font(&font, glyph_id, &[]).expect("Fail");
+            let glyph2 =
+                Glyph::new_from_font(&font, glyph_id, &[("wght", 800.0).into()]).expect("Fail");
+            let analysis = analyze(&glyph1, &glyph2, None);
+            for (tolerance, kinkiness) in [(0.95, 0.5), (0.8, 0.2), (0.99, 2.0)] {
+                let types = |problems: Vec<Problem>| {
+                    problems
+                        .iter()
+                        .map(|p| (p.problem_type(), p.contour, p.node))
+                        .sorted()
+                        .collect::<Vec<_>>()
+                };
+                let expected = run_tests_with(
+                    &glyph1,
+                    &glyph2,
+                    &RunTestsOptions::default()
+                        .with_tolerance(tolerance)
+                        .with_kinkiness(kinkiness),
+                );
+                let actual = analysis.problems_at(&Tolerances {
+                    tolerance,
+                    kinkiness,
+                });
+                assert_eq!(types(actual), types(expected));
+            }
+        }
+    }
+
+    #[test]
+    fn test_figure_eight() {
+        let figure_eight = |size: f64| {
+            let mut path = BezPath::new();
+            path.move_to((0.0, 0.0));
+            path.line_to((size, size));
+            path.line_to((size, 0.0));
+            path.line_to((0.0, size));
+            path.close_path();
+            let glyph: Glyph = BezGlyph::new_from_paths(vec![path]).into();
+            glyph
+        };
+        let (glyph1, glyph2) = (figure_eight(100.0), figure_eight(300.0));
+        assert!(glyph1.green_vectors()[0][0].abs() < 1e-6);
+        let problems = run_tests_with(&glyph1, &glyph2, &RunTestsOptions::default());
+        assert_eq!(problems.len(), 1);
+        assert!(matches!(
+            problems[0].details,
+            ProblemDetails::UncheckedContour { .. }
+        ));
+    }
+
+    #[test]
+    fn test_closing_point() {
+        // A TrueType contour made only of off-curve points, drawn as it is
+        // by skrifa: starting from an implied on-curve point and returning to it
+        let mut bezglyph = BezGlyph::default();
+        bezglyph.next().move_to((0.0, 50.0));
+        bezglyph.qcurve_to(&[
+            (0.0, 0.0),
+            (100.0, 0.0),
+            (100.0, 100.0),
+            (0.0, 100.0),
+            (0.0, 50.0),
+        ]);
+        bezglyph.current().close_path();
+        let glyph: Glyph = bezglyph.into();
+        let points = &glyph.points[0];
+        // The start point isn't repeated at the end
+        assert_eq!(points.len(), 8);
+        assert_ne!(points.first(), points.last());
+        // The isomorphisms are computed from the same points
+        for characteristic in glyph.isomorphisms[0].iter() {
+            assert_eq!(characteristic.rotated_list.len() % points.len(), 0);
+            assert_eq!(
+                characteristic.rotated_list[0],
+                points[characteristic.rotation].point.to_vec2()
+            );
+        }
+        assert!(run_tests_with(&glyph, &glyph, &RunTestsOptions::default()).is_empty());
+    }
+
+    #[test]
+    fn test_mirrored() {
+        let fontdata = include_bytes!("../NotoSerif-Italic.ttf");
+        let font = FontRef::new(fontdata).expect("Can't parse font");
+        let glyph_id = font.charmap().map('o').unwrap();
+        let glyph = Glyph::new_from_font(&font, glyph_id, &[]).expect("Fail");
+        let mirrored = glyph.mirrored(Axis::X);
+        // Reflection keeps the winding direction and the number of points
+        assert_eq!(mirrored.points.len(), glyph.points.len());
+        for (ix, (a, b)) in glyph
+            .green_vectors()
+            .iter()
+            .zip(mirrored.green_vectors())
+            .enumerate()
+        {
+            assert!((a[0] - b[0]).abs() < 1e-6, "contour {}", ix);
+            assert_eq!(glyph.points[ix].len(), mirrored.points[ix].len());
+        }
+        // Mirroring twice gets back to where we started
+        let twice = mirrored.mirrored(Axis::X);
+        for (a, b) in glyph
+            .points
+            .iter()
+            .flatten()
+            .zip(twice.points.iter().flatten())
+        {
+            assert!((a.point - b.point).hypot() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_midpoint_bounds() {
+        let fontdata = include_bytes!("../NotoSerif-Italic.ttf");
+        let font = FontRef::new(fontdata).expect("Can't parse font");
+        let glyph_id = font.charmap().map('o').unwrap();
+        let glyph = Glyph::new_from_font(&font, glyph_id, &[]).expect("Fail");
+        let moved = glyph.transformed(Affine::translate((100.0, 0.0)));
+        let bounds = glyph.bounds().unwrap();
+        let midpoint = glyph.midpoint_bounds(&moved).unwrap();
+        assert!((midpoint.x0 - (bounds.x0 + 50.0)).abs() < 1e-6);
+        assert!((midpoint.x1 - (bounds.x1 + 50.0)).abs() < 1e-6);
+        assert!((midpoint.y0 - bounds.y0).abs() < 1e-6);
+        assert!((midpoint.y1 - bounds.y1).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_kink_trajectory() {
+        let contour = |points: [(f64, f64); 4]| -> Vec<GlyfPoint> {
+            points
+                .into_iter()
+                .map(|(x, y)| GlyfPoint {
+                    point: Point::new(x, y),
+                    is_control: true,
+                })
+                .collect()
+        };
+        // Point 1 is on a straight line in both masters, but not in between
+        let contour0 = contour([(0.0, 0.0), (50.0, 0.0), (200.0, 0.0), (100.0, -100.0)]);
+        let contour1 = contour([(0.0, 0.0), (0.0, 150.0), (0.0, 200.0), (-100.0, 100.0)]);
+        let trajectory = kink_trajectory(&contour0, &contour1, 1, 5);
+        assert_eq!(trajectory.len(), 5);
+        assert_eq!(trajectory[0], (0.0, 0.0));
+        assert_eq!(trajectory[4], (1.0, 0.0));
+        assert!(trajectory[2].1 > 1.0);
+    }
+
+    #[test]
+    fn test_contour_perimeter() {
+        let square = Rect::new(0.0, 0.0, 100.0, 100.0).to_path(0.1);
+        let glyph: Glyph = BezGlyph::new_from_paths(vec![square]).into();
+        assert!((glyph.contour_perimeter(0).unwrap() - 400.0).abs() < 1e-6);
+        let density = glyph.contour_point_density(0).unwrap();
+        assert!((density - glyph.points[0].len() as f64 / 400.0).abs() < 1e-9);
+        assert_eq!(glyph.contour_perimeter(1), None);
+    }
+
+    #[test]
+    fn test_point_collision() {
+        // The bottom two points swap places, meeting in the middle
+        let glyph_a = polygon(&[(0.0, 0.0), (100.0, 0.0), (100.0, 100.0), (0.0, 100.0)]);
+        let glyph_b = polygon(&[(100.0, 0.0), (0.0, 0.0), (100.0, 100.0), (0.0, 100.0)]);
+        let problems = test_point_collisions(&glyph_a, &glyph_b, &RunTestsOptions::default());
+        assert!(problems.iter().any(|problem| matches!(
+            problem.details,
+            ProblemDetails::PointCollision { t, .. } if (t - 0.5).abs() < 1e-9
+        )));
+    }
+
+    #[test]
+    fn test_contour_mapping() {
+        let fontdata = include_bytes!("../variable_ttf/TwisterTest-VF.ttf");
+        let font = FontRef::new(fontdata).expect("Can't parse font");
+        let glyph_id = font.charmap().map('A').unwrap();
+        let glyph1 = Glyph::new_from_font(&font, glyph_id, &[]).expect("Fail");
+        let glyph2 =
+            Glyph::new_from_font(&font, glyph_id, &[("wght", 800.0).into()]).expect("Fail");
+        let options = RunTestsOptions::default();
+        assert_eq!(contour_mapping(&glyph1, &glyph2, &options), vec![2, 1, 0]);
+        assert_eq!(explain_contour_order(&glyph1, &glyph2, &options).len(), 2);
+        // Ignoring the contour order tests contours at the same index
+        let ignoring = RunTestsOptions::default().with_ignore_contour_order(true);
+        assert_eq!(contour_mapping(&glyph1, &glyph2, &ignoring), vec![0, 1, 2]);
+        assert!(explain_contour_order(&glyph1, &glyph2, &ignoring).is_empty());
+
+        // Glyphs with different numbers of contours can't be matched up
+        let square = polygon(&[(0.0, 0.0), (100.0, 0.0), (100.0, 100.0), (0.0, 100.0)]);
+        assert_eq!(contour_mapping(&glyph1, &square, &options), vec![0, 1, 2]);
+        assert!(explain_contour_order(&glyph1, &square, &options).is_empty());
+        assert_eq!(interpolated_contour_weight(&glyph1, &square, 1, 0.5), None);
+    }
+
+    #[test]
+    fn test_contour_direction() {
+        let square = polygon(&[(0.0, 0.0), (100.0, 0.0), (100.0, 100.0), (0.0, 100.0)]);
+        // The same square, wound the other way round
+        let reversed = polygon(&[(0.0, 0.0), (0.0, 100.0), (100.0, 100.0), (100.0, 0.0)]);
+        let problems = run_tests_with(&square, &reversed, &RunTestsOptions::default());
+        // This is reported as a change of direction, not a reversed start point
+        assert!(problems.iter().any(|p| {
+            matches!(p.details, ProblemDetails::ContourDirection) && p.contour == Some(0)
+        }));
+        assert!(!problems
+            .iter()
+            .any(|p| matches!(p.details, ProblemDetails::WrongStartPoint { .. })));
+    }
+
+    #[test]
+    fn test_contour_order_reversed() {
+        let big = [(0.0, 0.0), (100.0, 0.0), (100.0, 100.0), (0.0, 100.0)];
+        let big_reversed = [(0.0, 0.0), (0.0, 100.0), (100.0, 100.0), (100.0, 0.0)];
+        let small = [(300.0, 0.0), (350.0, 0.0), (350.0, 50.0), (300.0, 50.0)];
+        let glyph_a: Glyph =
+            BezGlyph::new_from_paths(vec![closed_path(&big), closed_path(&small)]).into();
+        // The contours are swapped, and the big one is also wound the other way
+        let glyph_b: Glyph =
+            BezGlyph::new_from_paths(vec![closed_path(&small), closed_path(&big_reversed)]).into();
+        let problems = run_tests_with(&glyph_a, &glyph_b, &RunTestsOptions::default());
+        let order = problems
+            .iter()
+            .find_map(|p| match &p.details {
+                ProblemDetails::ContourOrder {
+                    order_2, reversed, ..
+                } => Some((order_2.clone(), reversed.clone())),
+                _ => None,
+            })
+            .expect("No contour order problem");
+        assert_eq!(order, (vec![1, 0], vec![0]));
+    }
+
+    #[test]
+    fn test_is_interpolatable() {
+        let square = polygon(&[(0.0, 0.0), (100.0, 0.0), (100.0, 100.0), (0.0, 100.0)]);
+        let options = RunTestsOptions::default();
+        assert!(is_interpolatable(&square, &square, &options));
+        // The bottom two points swap places, so the midway outline twists
+        // into a bow tie; none of the problems found has a badness, but the
+        // glyph still draws wrongly
+        let crossed = polygon(&[(100.0, 0.0), (0.0, 0.0), (100.0, 100.0), (0.0, 100.0)]);
+        let problems = run_tests_with(&square, &crossed, &options);
+        assert!(problems.iter().all(|p| p.badness.is_none()));
+        assert!(problems
+            .iter()
+            .any(|p| matches!(p.details, ProblemDetails::FillRuleArtifact { .. })));
+        assert!(!is_interpolatable(&square, &crossed, &options));
+        assert_eq!(min_passing_tolerance(&problems), None);
+    }
+
+    #[test]
+    fn test_point_crossing_closing_segment() {
+        // Only the segment from the last point back to the first reverses
+        let glyph_a = polygon(&[(0.0, 0.0), (100.0, 0.0), (100.0, 100.0), (0.0, 100.0)]);
+        let glyph_b = polygon(&[(0.0, 200.0), (100.0, 0.0), (100.0, 100.0), (0.0, 100.0)]);
+        let problems = pointorder::test_point_ordering(
+            &glyph_a,
+            &glyph_b,
+            &glyph_a.points[0],
+            &glyph_b.points[0],
+            0,
+        );
+        assert_eq!(problems.len(), 1);
+        assert_eq!(problems[0].node, Some(3));
+        assert!(matches!(
+            problems[0].details,
+            ProblemDetails::PointCrossing { next_node: 0 }
+        ));
+    }
+
+    #[test]
+    fn test_run_tests_with_options() {
+        let fontdata = include_bytes!("../variable_ttf/TwisterTest-VF.ttf");
+        let font = FontRef::new(fontdata).expect("Can't parse font");
+        let glyph_id = font.charmap().map('A').unwrap();
+        let glyph1 = Glyph::new_from_font(&font, glyph_id, &[]).expect("Fail");
+        let glyph2 =
+            Glyph::new_from_font(&font, glyph_id, &[("wght", 800.0).into()]).expect("Fail");
+        let options = RunTestsOptions::default()
+            .with_tolerance(0.8)
+            .with_upem(1000);
+        let expected = run_tests(&glyph1, &glyph2, Some(0.8), None, Some(1000));
+        let actual = run_tests_with(&glyph1, &glyph2, &options);
+        assert_eq!(
+            serde_json::to_value(&actual).unwrap(),
+            serde_json::to_value(&expected).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_prefer_identity_on_ties() {
+        let columns = |matching: &[munkres::Position]| {
+            matching.iter().map(|pos| pos.column).collect::<Vec<_>>()
+        };
+        let swapped = || {
+            vec![
+                munkres::Position { row: 0, column: 1 },
+                munkres::Position { row: 1, column: 0 },
+                munkres::Position { row: 2, column: 2 },
+            ]
+        };
+        // Swapping the first two contours costs 0 + 2, the same as keeping
+        // them in place at 1 + 1, so the original order is kept
+        let tied = [1.0, 0.0, 5.0, 2.0, 1.0, 5.0, 5.0, 5.0, 0.0];
+        let mut matching = swapped();
+        contourorder::prefer_identity_on_ties(&mut matching, &tied, 3);
+        assert_eq!(columns(&matching), vec![0, 1, 2]);
+        // A swap which is genuinely cheaper is left alone
+        let cheaper = [5.0, 0.0, 5.0, 0.0, 5.0, 5.0, 5.0, 5.0, 0.0];
+        let mut matching = swapped();
+        contourorder::prefer_identity_on_ties(&mut matching, &cheaper, 3);
+        assert_eq!(columns(&matching), vec![1, 0, 2]);
+    }
+
+    #[test]
+    fn test_max_contour_distance() {
+        let square = |x: f64, size: f64| {
+            closed_path(&[(x, 0.0), (x + size, 0.0), (x + size, size), (x, size)])
+        };
+        // The two contours swap sizes but stay where they are, so each looks
+        // more like the other glyph's contour 150 units away
+        let glyph_a: Glyph =
+            BezGlyph::new_from_paths(vec![square(0.0, 50.0), square(150.0, 200.0)]).into();
+        let glyph_b: Glyph =
+            BezGlyph::new_from_paths(vec![square(0.0, 200.0), square(150.0, 50.0)]).into();
+        let options = RunTestsOptions::default();
+        assert_eq!(contour_mapping(&glyph_a, &glyph_b, &options), vec![1, 0]);
+        // Contours which are too far apart can't be matched with each other
+        let nearby = RunTestsOptions::default().with_max_contour_distance(100.0);
+        assert_eq!(contour_mapping(&glyph_a, &glyph_b, &nearby), vec![0, 1]);
+        let distant = RunTestsOptions::default().with_max_contour_distance(200.0);
+        assert_eq!(contour_mapping(&glyph_a, &glyph_b, &distant), vec![1, 0]);
+    }
+
+    #[test]
+    fn test_containment_change() {
+        let outer = [(0.0, 0.0), (100.0, 0.0), (100.0, 100.0), (0.0, 100.0)];
+        // The same square, starting at the opposite corner, so the midway
+        // outline collapses to a point
+        let outer_rotated = [(100.0, 100.0), (0.0, 100.0), (0.0, 0.0), (100.0, 0.0)];
+        let inner = [(10.0, 10.0), (30.0, 10.0), (30.0, 30.0), (10.0, 30.0)];
+        let m0_curves = vec![closed_path(&outer), closed_path(&inner)];
+        let m1_curves = vec![closed_path(&outer_rotated), closed_path(&inner)];
+        let glyph_a: Glyph = BezGlyph::new_from_paths(m0_curves.clone()).into();
+        let glyph_b: Glyph = BezGlyph::new_from_paths(m1_curves.clone()).into();
+        let midpoint: Vec<Option<BezPath>> = m0_curves
+            .iter()
+            .zip(m1_curves.iter())
+            .map(|(c0, c1)| lerp_curve(c0, c1))
+            .collect();
+        let problems =
+            containment::test_containment(&glyph_a, &glyph_b, &m0_curves, &m1_curves, &midpoint);
+        assert_eq!(problems.len(), 1);
+        assert_eq!(problems[0].contour, Some(1));
+        assert!(matches!(
+            problems[0].details,
+            ProblemDetails::ContainmentChange {
+                container: 0,
+                inside_in_masters: true
+            }
+        ));
+        // Nothing changes if the outer contour keeps its start point
+        let midpoint: Vec<Option<BezPath>> = m0_curves.iter().cloned().map(Some).collect();
+        assert!(containment::test_containment(
+            &glyph_a, &glyph_a, &m0_curves, &m0_curves, &midpoint
+        )
+        .is_empty());
+    }
+
+    #[test]
+    fn test_fill_rule_artifact() {
+        let midpoint = |a: &Glyph, b: &Glyph| -> Vec<Option<BezPath>> {
+            a.curves
+                .iter()
+                .zip(b.curves.iter())
+                .map(|(c0, c1)| lerp_curve(c0, c1))
+                .collect()
+        };
+        let square = polygon(&[(0.0, 0.0), (100.0, 0.0), (100.0, 100.0), (0.0, 100.0)]);
+        // A square growing to twice the size fills a tenth less than the
+        // average of the masters at the midpoint, which is expected
+        let big = polygon(&[(0.0, 0.0), (200.0, 0.0), (200.0, 200.0), (0.0, 200.0)]);
+        let mid = midpoint(&square, &big);
+        assert!(
+            fillrule::test_fill_rule(&square, &big, &square.curves, &big.curves, &mid).is_none()
+        );
+        // The bottom two points swap places, so the second master is a bow
+        // tie and the midpoint is a triangle
+        let crossed = polygon(&[(100.0, 0.0), (0.0, 0.0), (100.0, 100.0), (0.0, 100.0)]);
+        let mid = midpoint(&square, &crossed);
+        let problem =
+            fillrule::test_fill_rule(&square, &crossed, &square.curves, &crossed.curves, &mid)
+                .expect("No fill rule artifact");
+        match problem.details {
+            ProblemDetails::FillRuleArtifact {
+                filled_area,
+                expected_area,
+            } => {
+                assert!((filled_area - 5000.0).abs() < 200.0);
+                assert!((expected_area - 7500.0).abs() < 200.0);
+            }
+            other => panic!("Expected a fill rule artifact, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_metrics_drift() {
+        // A square at `x`, in a glyph with the given advance width
+        let master = |name: &str, index: usize, x: f64, advance: f64| {
+            let mut glyph = polygon(&[(x, 0.0), (x + 100.0, 0.0), (x + 100.0, 100.0), (x, 100.0)]);
+            glyph.master_name = name.to_string();
+            glyph.master_index = index;
+            glyph.advance_width = Some(advance);
+            glyph
+        };
+        // The advance widths grow steadily, but the square moves right and
+        // then back left again
+        let light = master("Light", 0, 10.0, 500.0);
+        let regular = master("Regular", 1, 50.0, 550.0);
+        let bold = master("Bold", 2, 20.0, 600.0);
+        let problems = test_metrics(&[&light, &regular, &bold]);
+        assert_eq!(problems.len(), 1);
+        assert_eq!(problems[0].master_1_name, "Light");
+        assert_eq!(problems[0].master_2_name, "Bold");
+        match &problems[0].details {
+            ProblemDetails::MetricsDrift {
+                metric,
+                middle_master_name,
+                middle_value,
+                ..
+            } => {
+                assert_eq!(*metric, Metric::Lsb);
+                assert_eq!(middle_master_name, "Regular");
+                assert!((middle_value - 50.0).abs() < 1e-9);
+            }
+            other => panic!("Expected a metrics drift, got {:?}", other),
+        }
+        // Two masters have nothing in between to drift
+        assert!(test_metrics(&[&light, &bold]).is_empty());
+    }
+
+    #[test]
+    fn test_limit_problems() {
+        let square = [(0.0, 0.0), (100.0, 0.0), (100.0, 100.0), (0.0, 100.0)];
+        let mut light = polygon(&square);
+        light.master_name = "Light".to_string();
+        let mut regular = polygon(&square);
+        regular.master_name = "Regular".to_string();
+        regular.master_index = 1;
+        let mut bold = polygon(&square);
+        bold.master_name = "Bold".to_string();
+        bold.master_index = 2;
+        // Problems from both master pairs of one glyph are limited together
+        let mut problems: Vec<Problem> = (0..3)
+            .map(|_| Problem::custom(&light, &regular, "test", "first pair"))
+            .chain((0..3).map(|_| Problem::custom(&regular, &bold, "test", "second pair")))
+            .collect();
+        limit_problems(&mut problems, 4);
+        assert_eq!(problems.len(), 5);
+        assert!(matches!(
+            problems[4].details,
+            ProblemDetails::MoreProblems { count: 2 }
+        ));
+        assert_eq!(problems[4].master_1_name, "Regular");
+        assert_eq!(problems[4].master_2_name, "Bold");
+        // Nothing changes when there are few enough problems
+        let before = problems.len();
+        limit_problems(&mut problems, 10);
+        assert_eq!(problems.len(), before);
+    }
+
+    #[test]
+    fn test_contour_turning_function() {
+        let square = Rect::new(0.0, 0.0, 100.0, 100.0).to_path(0.1);
+        let glyph: Glyph = BezGlyph::new_from_paths(vec![square]).into();
+        let turning = glyph.contour_turning_function(0);
+        let &(length, angle) = turning.last().unwrap();
+        assert!((length - 400.0).abs() < 1e-6);
+        assert!((angle.abs() - 2.0 * std::f64::consts::PI).abs() < 1e-6);
+        assert!(glyph.contour_turning_function(1).is_empty());
+    }
 }
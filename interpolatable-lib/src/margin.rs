@@ -0,0 +1,83 @@
+use greencurves::ComputeGreenStatistics;
+
+use crate::{
+    basiccompat::test_compatibility, contourorder::test_contour_order,
+    startingpoint::test_starting_point, stats_to_vectors, utils::lerp_curve, Glyph,
+};
+
+/// The smallest match ratio of a contour's area at the midway interpolation
+fn weight_ratio(m0_vector: &[f64], m1_vector: &[f64], mid_size: f64) -> f64 {
+    let size0 = m0_vector[0] * m0_vector[0];
+    let size1 = m1_vector[0] * m1_vector[0];
+    let overweight = size0.max(size1);
+    let underweight = (size0 * size1).sqrt();
+    let over_ratio = if mid_size > 0.0 {
+        overweight / mid_size
+    } else {
+        1.0
+    };
+    let under_ratio = if underweight > 0.0 {
+        mid_size / underweight
+    } else {
+        1.0
+    };
+    over_ratio.min(under_ratio).min(1.0)
+}
+
+/// How much headroom a pair of glyphs has before any check would report a problem
+///
+/// This is the lowest match ratio found by the contour order, start point and
+/// weight checks, minus the `tolerance` (default 0.95); it is positive if the
+/// glyphs pass these checks and negative if they fail. Unlike the problems
+/// returned by [crate::run_tests], it is available when there are no problems,
+/// so it can be used to watch glyphs drifting towards the tolerance. Kinks are
+/// not included, as they are only measured at points which are already suspect.
+///
+/// Returns `None` if the glyphs are not structurally compatible.
+pub fn tolerance_margin(glyph_a: &Glyph, glyph_b: &Glyph, tolerance: Option<f64>) -> Option<f64> {
+    let tolerance = tolerance.unwrap_or(0.95);
+    if !test_compatibility(glyph_a, glyph_b).is_empty() {
+        return None;
+    }
+    let (mut ratio, matching) = test_contour_order(glyph_a, glyph_b, None);
+    let (m1_isomorphisms, m1_vectors, m1_curves) = if let Some(matching) = matching.as_ref() {
+        (
+            matching.reorder(&glyph_b.isomorphisms),
+            matching.reorder(&glyph_b.green_vectors),
+            matching.reorder(&glyph_b.curves),
+        )
+    } else {
+        (
+            glyph_b.isomorphisms.clone(),
+            glyph_b.green_vectors.clone(),
+            glyph_b.curves.clone(),
+        )
+    };
+    for (ix, (contour_0, contour_1)) in glyph_a
+        .isomorphisms
+        .iter()
+        .zip(m1_isomorphisms.iter())
+        .enumerate()
+    {
+        if let Some((this_ratio, ..)) = test_starting_point(
+            glyph_b,
+            contour_0,
+            contour_1,
+            &glyph_a.green_vectors,
+            &m1_vectors,
+            ix,
+            None,
+        ) {
+            ratio = ratio.min(this_ratio);
+        }
+        if let Some(mid) = lerp_curve(&glyph_a.curves[ix], &m1_curves[ix]) {
+            let mid_vector = stats_to_vectors(&mid.green_statistics());
+            ratio = ratio.min(weight_ratio(
+                &glyph_a.green_vectors[ix],
+                &m1_vectors[ix],
+                mid_vector[0] * mid_vector[0],
+            ));
+        }
+    }
+    Some(ratio - tolerance)
+}
@@ -0,0 +1,53 @@
+use crate::{
+    problems::{Metric, Problem},
+    Glyph,
+};
+
+/// Changes in metrics smaller than this (in font units) are treated as rounding noise
+const METRICS_EPSILON: f64 = 0.5;
+
+/// Test that advance widths and left sidebearings interpolate monotonically
+///
+/// The glyphs must be given in order along a single axis (e.g. the lightest
+/// master, the default, then the heaviest master). A master whose advance
+/// width or left sidebearing lies outside the range of its two neighbours
+/// makes the spacing wobble back and forth as the axis is moved, and is
+/// reported as a `MetricsDrift` problem against the two neighbouring masters.
+pub fn test_metrics(glyphs: &[&Glyph]) -> Vec<Problem> {
+    let mut problems = vec![];
+    for triple in glyphs.windows(3) {
+        if let [before, middle, after] = triple {
+            for (metric, values) in [
+                (
+                    Metric::AdvanceWidth,
+                    [
+                        before.advance_width,
+                        middle.advance_width,
+                        after.advance_width,
+                    ],
+                ),
+                (Metric::Lsb, [before.lsb(), middle.lsb(), after.lsb()]),
+            ] {
+                if let [Some(value_1), Some(middle_value), Some(value_2)] = values {
+                    let d0 = middle_value - value_1;
+                    let d1 = value_2 - middle_value;
+                    if d0.abs() > METRICS_EPSILON
+                        && d1.abs() > METRICS_EPSILON
+                        && d0.signum() != d1.signum()
+                    {
+                        problems.push(Problem::metrics_drift(
+                            before,
+                            after,
+                            metric,
+                            middle,
+                            value_1,
+                            middle_value,
+                            value_2,
+                        ));
+                    }
+                }
+            }
+        }
+    }
+    problems
+}
@@ -0,0 +1,139 @@
+use std::collections::HashMap;
+
+use crate::{Check, Statistics};
+
+/// The settings for [crate::run_tests_with]
+///
+/// Every setting is optional; the defaults are the same as passing `None`
+/// (or `false`) for everything to [crate::run_tests]. Set them with the
+/// builder-style setters (such as `RunTestsOptions::default().with_upem(2048)`),
+/// or with struct update syntax and `..Default::default()`. New settings
+/// should be added here rather than as arguments to the `run_tests` functions.
+#[derive(Default, Clone)]
+pub struct RunTestsOptions<'c> {
+    /// The match ratio (1.0 being a perfect match) below which problems are
+    /// reported; defaults to 0.95. See [crate::Badness] for how this relates to
+    /// the badness of the reported problems.
+    pub tolerance: Option<f64>,
+    /// The maximum tolerance for kinks; defaults to 0.5
+    pub kinkiness: Option<f64>,
+    /// The angle threshold (as a sine) used when looking for kinks; defaults
+    /// to 0.1. Smaller values find kinks in gentler curves.
+    pub smoothness: Option<f64>,
+    /// The font's units per em; defaults to 1000
+    pub upem: Option<u16>,
+    /// If set, contours whose centers of mass are further apart than this (in
+    /// font units) are never matched to each other when checking contour order
+    pub max_contour_distance: Option<f64>,
+    /// Contours whose points move less than this in total (in font units)
+    /// between the two glyphs are not checked; defaults to
+    /// [crate::DEFAULT_MIN_CONTOUR_MOVEMENT]
+    pub min_contour_movement: Option<f64>,
+    /// The interpolation positions at which to test for overweight and
+    /// underweight contours; defaults to `[0.5]`
+    pub weight_samples: Option<Vec<f64>>,
+    /// How many points either side of a contour's start point a proposed start
+    /// point may be and still be checked again for slightly rotated,
+    /// near-circular contours; defaults to a tenth of the contour's points (at
+    /// least three)
+    pub start_point_leeway: Option<usize>,
+    /// Skip the contour order check, and test each contour against the
+    /// contour at the same index in the other glyph
+    pub ignore_contour_order: bool,
+    /// Which [Statistics] to use for the start point and weight checks
+    pub statistics: Statistics,
+    /// Tolerances which replace `tolerance` in the start point, weight and
+    /// kink checks for some contours, by contour index in the first glyph.
+    /// This allows a single contour which is known to be quirky to be treated
+    /// more leniently without loosening the checks on the whole glyph.
+    pub contour_tolerances: HashMap<usize, f64>,
+    /// Also check for contours which have on-curve points at extrema in one
+    /// master but not the other, reporting advisory `MissingExtrema` problems
+    pub check_extrema: bool,
+    /// Also check for cubic handles which change length drastically between
+    /// masters, reporting advisory `HandleLength` problems
+    pub check_handle_length: bool,
+    /// Also check for contours whose start point is smooth in one master but
+    /// a corner in the other, reporting advisory `StartPointTypeMismatch`
+    /// problems
+    pub check_start_point_type: bool,
+    /// Additional [Check]s to run alongside the built-in checks
+    pub custom_checks: Vec<&'c dyn Check>,
+}
+
+impl<'c> RunTestsOptions<'c> {
+    pub fn with_tolerance(mut self, tolerance: f64) -> Self {
+        self.tolerance = Some(tolerance);
+        self
+    }
+
+    pub fn with_kinkiness(mut self, kinkiness: f64) -> Self {
+        self.kinkiness = Some(kinkiness);
+        self
+    }
+
+    pub fn with_smoothness(mut self, smoothness: f64) -> Self {
+        self.smoothness = Some(smoothness);
+        self
+    }
+
+    pub fn with_upem(mut self, upem: u16) -> Self {
+        self.upem = Some(upem);
+        self
+    }
+
+    pub fn with_max_contour_distance(mut self, max_contour_distance: f64) -> Self {
+        self.max_contour_distance = Some(max_contour_distance);
+        self
+    }
+
+    pub fn with_min_contour_movement(mut self, min_contour_movement: f64) -> Self {
+        self.min_contour_movement = Some(min_contour_movement);
+        self
+    }
+
+    pub fn with_weight_samples(mut self, weight_samples: Vec<f64>) -> Self {
+        self.weight_samples = Some(weight_samples);
+        self
+    }
+
+    pub fn with_start_point_leeway(mut self, start_point_leeway: usize) -> Self {
+        self.start_point_leeway = Some(start_point_leeway);
+        self
+    }
+
+    pub fn with_ignore_contour_order(mut self, ignore_contour_order: bool) -> Self {
+        self.ignore_contour_order = ignore_contour_order;
+        self
+    }
+
+    pub fn with_statistics(mut self, statistics: Statistics) -> Self {
+        self.statistics = statistics;
+        self
+    }
+
+    pub fn with_contour_tolerances(mut self, contour_tolerances: HashMap<usize, f64>) -> Self {
+        self.contour_tolerances = contour_tolerances;
+        self
+    }
+
+    pub fn with_check_extrema(mut self, check_extrema: bool) -> Self {
+        self.check_extrema = check_extrema;
+        self
+    }
+
+    pub fn with_check_handle_length(mut self, check_handle_length: bool) -> Self {
+        self.check_handle_length = check_handle_length;
+        self
+    }
+
+    pub fn with_check_start_point_type(mut self, check_start_point_type: bool) -> Self {
+        self.check_start_point_type = check_start_point_type;
+        self
+    }
+
+    pub fn with_custom_check(mut self, check: &'c dyn Check) -> Self {
+        self.custom_checks.push(check);
+        self
+    }
+}
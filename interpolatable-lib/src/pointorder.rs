@@ -0,0 +1,110 @@
+use crate::{
+    basiccompat::test_compatibility, contour_mapping, problems::Problem, GlyfPoint, Glyph,
+    RunTestsOptions,
+};
+
+/// Segments shorter than this (in font units) are too short to have a meaningful direction
+const MIN_SEGMENT_LENGTH: f64 = 1.0;
+/// Points which come within this distance (in font units) of each other have met
+const COLLISION_DISTANCE: f64 = 0.5;
+
+/// Test for consecutive on-curve points which swap their relative order between masters
+///
+/// If the vector from one on-curve point to the next points in opposite
+/// directions in the two masters, the points cross over each other during
+/// interpolation and the contour tangles. Each such pair is reported as a
+/// `PointCrossing` problem on the first of the two nodes.
+pub(crate) fn test_point_ordering<'a>(
+    glyph_a: &'a Glyph,
+    glyph_b: &'a Glyph,
+    contour0: &[GlyfPoint],
+    contour1: &[GlyfPoint],
+    ix: usize,
+) -> Vec<Problem> {
+    let mut problems = vec![];
+    let oncurves: Vec<(usize, &GlyfPoint, &GlyfPoint)> = contour0
+        .iter()
+        .zip(contour1.iter())
+        .enumerate()
+        .filter(|(_, (pt0, pt1))| pt0.is_control && pt1.is_control)
+        .map(|(i, (pt0, pt1))| (i, pt0, pt1))
+        .collect();
+    // Include the segment which closes the contour, unless there are only two
+    // on-curve points, when it is the same pair the other way round
+    let pairs = if oncurves.len() > 2 {
+        oncurves.len()
+    } else {
+        oncurves.len().saturating_sub(1)
+    };
+    let next = oncurves.iter().cycle().skip(1);
+    for ((i, this0, this1), (j, next0, next1)) in oncurves.iter().zip(next).take(pairs) {
+        let d0 = next0.point - this0.point;
+        let d1 = next1.point - this1.point;
+        if d0.length() < MIN_SEGMENT_LENGTH || d1.length() < MIN_SEGMENT_LENGTH {
+            continue;
+        }
+        if d0.dot(d1) < 0.0 {
+            problems.push(Problem::point_crossing(glyph_a, glyph_b, ix, *i, *j));
+        }
+    }
+    problems
+}
+
+/// Test for consecutive on-curve points which meet during interpolation
+///
+/// The vector between two on-curve points changes linearly with the
+/// interpolation position `t`, so the position at which the points are
+/// closest can be found exactly rather than by sampling. Pairs which are
+/// apart in both masters but come within half a font unit of each other in
+/// between collapse the segment joining them, and are reported as
+/// `PointCollision` problems on the first of the two nodes, with the `t` at
+/// which they meet. Points which meet have usually swapped places, so this
+/// often accompanies a `PointCrossing` problem, but says where the segment
+/// collapses. Contours are matched as [crate::run_tests_with] matches them
+/// with the same `options`.
+pub fn test_point_collisions(
+    glyph_a: &Glyph,
+    glyph_b: &Glyph,
+    options: &RunTestsOptions,
+) -> Vec<Problem> {
+    let mut problems = vec![];
+    if !test_compatibility(glyph_a, glyph_b).is_empty() {
+        return problems;
+    }
+    let mapping = contour_mapping(glyph_a, glyph_b, options);
+    for (ix, &jx) in mapping.iter().enumerate() {
+        let (Some(contour0), Some(contour1)) = (glyph_a.points.get(ix), glyph_b.points.get(jx))
+        else {
+            continue;
+        };
+        let oncurves: Vec<usize> = contour0
+            .iter()
+            .zip(contour1.iter())
+            .enumerate()
+            .filter(|(_, (pt0, pt1))| pt0.is_control && pt1.is_control)
+            .map(|(i, _)| i)
+            .collect();
+        // Include the segment which closes the contour
+        let next = oncurves.iter().cycle().skip(1);
+        for (&i, &j) in oncurves.iter().zip(next).take(oncurves.len()) {
+            if i == j {
+                continue;
+            }
+            let d0 = contour0[j].point - contour0[i].point;
+            let d1 = contour1[j].point - contour1[i].point;
+            if d0.length() < COLLISION_DISTANCE || d1.length() < COLLISION_DISTANCE {
+                continue;
+            }
+            // The distance |d0 + t(d1 - d0)| is smallest at t = -d0.(d1 - d0) / |d1 - d0|^2
+            let change = d1 - d0;
+            let t = -d0.dot(change) / change.hypot2();
+            if !(0.0..=1.0).contains(&t) {
+                continue;
+            }
+            if (d0 + change * t).length() < COLLISION_DISTANCE {
+                problems.push(Problem::point_collision(glyph_a, glyph_b, ix, i, j, t));
+            }
+        }
+    }
+    problems
+}
@@ -1,8 +1,46 @@
-use serde::Serialize;
+use std::{cmp::Ordering, fmt};
 
-use crate::Glyph;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Serialize)]
+use kurbo::Point;
+
+use crate::{contour_mapping, Glyph, RunTestsOptions};
+
+/// How bad a problem is, from 0.0 (a perfect match) to 1.0 (as bad as it gets)
+///
+/// The graded checks measure how closely two masters match as a ratio, where
+/// 1.0 is a perfect match and lower is worse, and report a problem when the
+/// ratio falls below the tolerance passed to [crate::run_tests]. A problem's
+/// badness is one minus that ratio, so that higher always means worse: a
+/// problem which only just fails the default tolerance of 0.95 has a badness
+/// of just over 0.05. [Badness::ratio] gives the match ratio back.
+///
+/// Only `ContourOrder`, `WrongStartPoint`, `ContourDirection`, `Overweight`,
+/// `Underweight` and `Kink` problems are graded. Every other kind of problem
+/// has no badness, either because it is reported at any tolerance (such as a
+/// structural incompatibility) or because it is advisory (see
+/// [Problem::is_advisory]).
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Badness(f64);
+
+impl Badness {
+    /// The badness of a problem whose match ratio was `ratio`
+    pub fn from_ratio(ratio: f64) -> Self {
+        Badness((1.0 - ratio).clamp(0.0, 1.0))
+    }
+
+    /// The match ratio of the problem, where 1.0 is a perfect match
+    pub fn ratio(self) -> f64 {
+        1.0 - self.0
+    }
+
+    pub fn value(self) -> f64 {
+        self.0
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Problem {
     pub master_1_name: String,
     pub master_2_name: String,
@@ -10,12 +48,27 @@ pub struct Problem {
     pub master_2_index: usize,
     #[serde(flatten)]
     pub details: ProblemDetails,
-    pub tolerance: Option<f64>,
+    /// How bad the problem is, or `None` for kinds of problem which aren't graded
+    pub badness: Option<Badness>,
     pub contour: Option<usize>,
     pub node: Option<usize>,
 }
 
-#[derive(Debug, Serialize)]
+/// A place in a glyph for a font editor to jump to; see [Problem::navigation_target]
+///
+/// The glyph isn't included, as problems are always reported per glyph.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NavTarget {
+    /// The index of the master, as in [Problem::master_2_index]
+    pub master_index: usize,
+    pub master_name: String,
+    /// The contour to select, if the problem is with a particular contour
+    pub contour: Option<usize>,
+    /// The point to select within the contour, if the problem is at a particular point
+    pub node: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type")]
 pub enum ProblemDetails {
     PathCount {
@@ -33,20 +86,462 @@ pub enum ProblemDetails {
     ContourOrder {
         order_1: Vec<usize>,
         order_2: Vec<usize>,
+        /// The positions (in `order_1`) whose matched contour is also wound
+        /// in the opposite direction
+        reversed: Vec<usize>,
     },
     WrongStartPoint {
         proposed_point: usize,
         reverse: bool,
+        /// The number of nodes between the current and proposed start points
+        rotation_delta: usize,
+        /// How much to trust this problem, from 0 to 1
+        ///
+        /// Start points of near-circular contours are easily misjudged, so the
+        /// confidence is lower the more circle-like the contour is. See
+        /// [retain_confident_start_points].
+        confidence: f64,
+        /// Whether starting at the proposed point would make the contour
+        /// kink more sharply than it does now
+        creates_kink: bool,
     },
+    /// The contour has the same shape in both masters but is wound in opposite directions
+    ///
+    /// This is reported instead of a reversed `WrongStartPoint`, so that
+    /// intentional changes of direction can be told apart from (and ignored
+    /// separately from) other start point problems.
+    ContourDirection,
     Overweight {
         value_1: f64,
         value_2: f64,
+        /// The size of the contour in the first master, in thousandths of an em
+        permille_1: f64,
+        /// The size of the contour in the second master, in thousandths of an em
+        permille_2: f64,
+        /// The area at the midpoint as a proportion of the expected area
+        midway_ratio: f64,
+        /// The interpolation position at which the contour was tested
+        t: f64,
     },
     Underweight {
         value_1: f64,
         value_2: f64,
+        /// The size of the contour in the first master, in thousandths of an em
+        permille_1: f64,
+        /// The size of the contour in the second master, in thousandths of an em
+        permille_2: f64,
+        /// The area at the midpoint as a proportion of the expected area
+        midway_ratio: f64,
+        /// The interpolation position at which the contour was tested
+        t: f64,
+    },
+    Kink {
+        /// The deviation of the point from smoothness, in font units, sampled
+        /// across the interpolation as `(t, deviation)` pairs; only present
+        /// if requested with [crate::add_kink_trajectories]
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        trajectory: Option<Vec<(f64, f64)>>,
+    },
+    LikelyOverlapRemoval {
+        count_1: usize,
+        count_2: usize,
+        density_1: f64,
+        density_2: f64,
+    },
+    ContainmentChange {
+        container: usize,
+        inside_in_masters: bool,
+    },
+    CentroidJump {
+        reason: String,
+        distance: f64,
+    },
+    PointCrossing {
+        next_node: usize,
+    },
+    /// Two consecutive on-curve points meet during interpolation, collapsing the segment between them
+    PointCollision {
+        /// The on-curve point which meets the reported node
+        next_node: usize,
+        /// The interpolation position at which they meet
+        t: f64,
+    },
+    /// The midway interpolation, when filled, covers a very different area from the masters
+    FillRuleArtifact {
+        /// The area filled at the midway interpolation
+        filled_area: f64,
+        /// The average of the areas filled by the two masters
+        expected_area: f64,
+    },
+    /// A cubic handle changes length drastically, relative to its segment, between masters
+    HandleLength {
+        /// The handle length as a proportion of the chord in the first master
+        ratio_1: f64,
+        /// The handle length as a proportion of the chord in the second master
+        ratio_2: f64,
+    },
+    /// Matched contours have noticeably different shapes, once their position and scale are removed
+    FrechetDistance {
+        /// The Fréchet distance between the normalized contours, in units of the contours' spread
+        distance: f64,
+    },
+    /// A contour shrinks away or turns inside out when extrapolated beyond the masters
+    ExtrapolationCollapse {
+        /// The interpolation position (outside 0 to 1) at which the contour collapses
+        t: f64,
+        /// The contour's area at `t` as a proportion of the smaller master's;
+        /// negative if it has turned inside out
+        ratio: f64,
+    },
+    /// The font's own instance between two masters doesn't match the interpolation of their points
+    InstantiationDivergence {
+        /// The largest distance between a point of the instance and the interpolated point, in font units
+        distance: f64,
+    },
+    /// A smooth join between two curves loses its smoothness during interpolation
+    TangentDiscontinuity {
+        /// The angle between the handles at the midway interpolation, in degrees
+        angle: f64,
+    },
+    /// A curve segment bends one way in one master and the other way in the other
+    SegmentCurvatureFlip {
+        /// The index of the segment in the contour
+        segment: usize,
+        /// The direction the segment bends in the first master: 1 for
+        /// anticlockwise, -1 for clockwise
+        sign_1: i8,
+        /// The direction the segment bends in the second master
+        sign_2: i8,
+    },
+    /// One master has on-curve points at the contour's extrema and the other doesn't
+    MissingExtrema {
+        /// The number of segments in the first master with an extremum between their end points
+        missing_1: usize,
+        /// The number of segments in the second master with an extremum between their end points
+        missing_2: usize,
+    },
+    /// A contour starts at a smooth point in one master and a corner in the other
+    StartPointTypeMismatch {
+        /// Whether the start point is smooth in the first master
+        smooth_1: bool,
+        /// Whether the start point is smooth in the second master
+        smooth_2: bool,
+    },
+    /// A contour has a problem which a contour of the same shape in another glyph doesn't
+    SharedShapeInconsistency {
+        /// The type of the problem found in this contour
+        problem_type: String,
+        /// The glyph containing the contour of the same shape
+        other_glyph: String,
+        /// The index of the contour of the same shape in that glyph
+        other_contour: usize,
+    },
+    /// A contour which could not be (fully) analyzed
+    UncheckedContour {
+        reason: String,
+    },
+    /// The second master draws exactly the same outline as the first (the default)
+    IdenticalMaster,
+    /// A marker standing in for problems dropped by [limit_problems]
+    MoreProblems {
+        /// The number of problems which were dropped
+        count: usize,
+    },
+    /// A problem reported by a custom [crate::Check]
+    Custom {
+        check: String,
+        message: String,
+    },
+    MetricsDrift {
+        metric: Metric,
+        middle_master_name: String,
+        value_1: f64,
+        middle_value: f64,
+        value_2: f64,
     },
-    Kink,
+}
+
+/// A horizontal metric of a glyph, as checked by [crate::test_metrics]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Metric {
+    AdvanceWidth,
+    /// The left sidebearing: the left edge of the glyph's bounding box
+    Lsb,
+}
+
+impl fmt::Display for Metric {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Metric::AdvanceWidth => write!(f, "Advance width"),
+            Metric::Lsb => write!(f, "Left sidebearing"),
+        }
+    }
+}
+
+/// The strictest tolerance at which a glyph with these problems would pass
+///
+/// This is the lowest match ratio among the problems, so passing it as the
+/// `tolerance` to [crate::run_tests] would report none of them. Advisory
+/// problems (see [Problem::is_advisory]) don't stop a glyph passing, so are
+/// ignored. Returns `None` if any of the other problems (such as a structural
+/// incompatibility) would be reported at any tolerance.
+pub fn min_passing_tolerance(problems: &[Problem]) -> Option<f64> {
+    problems
+        .iter()
+        .filter(|problem| !problem.is_advisory())
+        .try_fold(1.0f64, |tolerance, problem| {
+            problem.badness.map(|b| tolerance.min(b.ratio()))
+        })
+}
+
+/// Drop `WrongStartPoint` problems whose confidence is below `min_confidence`
+///
+/// The start point check reports many false positives on near-circular
+/// contours, whose points can be rotated a little without changing the shape
+/// much; such problems are given a low confidence (see
+/// [ProblemDetails::WrongStartPoint]). A `min_confidence` of 0.5 removes most
+/// of them. Other problems are untouched.
+pub fn retain_confident_start_points(problems: &mut Vec<Problem>, min_confidence: f64) {
+    problems.retain(|p| match p.details {
+        ProblemDetails::WrongStartPoint { confidence, .. } => confidence >= min_confidence,
+        _ => true,
+    });
+}
+
+/// Keep at most `max` problems, replacing the rest with a `MoreProblems` marker
+///
+/// A single badly broken contour can produce dozens of problems (for example,
+/// one `NodeIncompatibility` per node), which drown out everything else in a
+/// report. This keeps the first `max` problems and appends a marker saying
+/// how many more there were, so the severity is still visible. The marker
+/// takes its master names from the first problem it replaces.
+pub fn limit_problems(problems: &mut Vec<Problem>, max: usize) {
+    if problems.len() <= max {
+        return;
+    }
+    let count = problems.len() - max;
+    let dropped = problems.split_off(max);
+    if let Some(first) = dropped.into_iter().next() {
+        problems.push(Problem {
+            master_1_name: first.master_1_name,
+            master_2_name: first.master_2_name,
+            master_1_index: first.master_1_index,
+            master_2_index: first.master_2_index,
+            contour: None,
+            node: None,
+            badness: None,
+            details: ProblemDetails::MoreProblems { count },
+        });
+    }
+}
+
+/// The outcome of checking a single glyph, for reporting coverage
+#[derive(Debug, Serialize)]
+#[serde(tag = "status")]
+pub enum GlyphStatus {
+    /// The glyph was checked and problems were found
+    Checked,
+    /// The glyph was checked and no problems were found
+    Clean,
+    /// The glyph was not checked
+    Skipped { reason: String },
+}
+
+impl fmt::Display for ProblemDetails {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProblemDetails::PathCount { count_1, count_2 } => {
+                write!(f, "Different number of contours ({} vs {})", count_1, count_2)
+            }
+            ProblemDetails::NodeCount { count_1, count_2 } => {
+                write!(f, "Different number of nodes ({} vs {})", count_1, count_2)
+            }
+            ProblemDetails::NodeIncompatibility { is_control_1, .. } => write!(
+                f,
+                "Node is {} in one master and {} in the other",
+                if *is_control_1 { "on-curve" } else { "off-curve" },
+                if *is_control_1 { "off-curve" } else { "on-curve" },
+            ),
+            ProblemDetails::ContourOrder {
+                order_1,
+                order_2,
+                reversed,
+            } => {
+                write!(
+                    f,
+                    "Contour order mismatch ({:?} vs {:?})",
+                    order_1, order_2
+                )?;
+                if !reversed.is_empty() {
+                    write!(f, ", and contours {:?} are also reversed", reversed)?;
+                }
+                Ok(())
+            }
+            ProblemDetails::WrongStartPoint {
+                proposed_point,
+                reverse,
+                rotation_delta,
+                creates_kink,
+                ..
+            } => write!(
+                f,
+                "Wrong start point: should start at node {} ({} nodes away){}{}",
+                proposed_point,
+                rotation_delta,
+                if *reverse { " and be reversed" } else { "" },
+                if *creates_kink {
+                    ", but this would create a kink"
+                } else {
+                    ""
+                }
+            ),
+            ProblemDetails::ContourDirection => {
+                write!(f, "Contour is wound in the opposite direction")
+            }
+            ProblemDetails::Overweight { midway_ratio, t, .. } => write!(
+                f,
+                "Contour becomes overweight ({:.0}% of expected area at t={})",
+                midway_ratio * 100.0,
+                t
+            ),
+            ProblemDetails::Underweight { midway_ratio, t, .. } => write!(
+                f,
+                "Contour becomes underweight ({:.0}% of expected area at t={})",
+                midway_ratio * 100.0,
+                t
+            ),
+            ProblemDetails::Kink { trajectory } => {
+                write!(f, "Kink in smooth point")?;
+                let worst = trajectory.iter().flatten().max_by(|a, b| a.1.total_cmp(&b.1));
+                if let Some((t, deviation)) = worst {
+                    write!(f, " (worst at t={:.2}, {:.1} units)", t, deviation)?;
+                }
+                Ok(())
+            }
+            ProblemDetails::LikelyOverlapRemoval {
+                count_1, count_2, ..
+            } => write!(
+                f,
+                "Different number of nodes ({} vs {}); overlaps were probably removed in one master",
+                count_1, count_2
+            ),
+            ProblemDetails::ContainmentChange {
+                container,
+                inside_in_masters,
+            } => write!(
+                f,
+                "Contour {} contour {} in the masters but not at the midpoint",
+                if *inside_in_masters { "is inside" } else { "is outside" },
+                container
+            ),
+            ProblemDetails::CentroidJump { reason, distance } => {
+                write!(f, "Centroid movement is {} ({:.1} units)", reason, distance)
+            }
+            ProblemDetails::PointCrossing { next_node } => {
+                write!(f, "Node crosses over node {} during interpolation", next_node)
+            }
+            ProblemDetails::PointCollision { next_node, t } => {
+                write!(f, "Node meets node {} at t={:.2}", next_node, t)
+            }
+            ProblemDetails::FillRuleArtifact {
+                filled_area,
+                expected_area,
+            } => write!(
+                f,
+                "Filled area at the midpoint is {:.0}% of what the masters suggest",
+                filled_area / expected_area * 100.0
+            ),
+            ProblemDetails::HandleLength { ratio_1, ratio_2 } => write!(
+                f,
+                "Handle length changes from {:.2} to {:.2} of the segment's chord",
+                ratio_1, ratio_2
+            ),
+            ProblemDetails::FrechetDistance { distance } => write!(
+                f,
+                "Contour shapes differ (normalized Fréchet distance {:.2})",
+                distance
+            ),
+            ProblemDetails::ExtrapolationCollapse { t, ratio } => write!(
+                f,
+                "Contour {} when extrapolated to t={} ({:.0}% of its area)",
+                if *ratio < 0.0 {
+                    "turns inside out"
+                } else {
+                    "collapses"
+                },
+                t,
+                ratio * 100.0
+            ),
+            ProblemDetails::InstantiationDivergence { distance } => write!(
+                f,
+                "Midway instance is {:.1} units away from the interpolated outline",
+                distance
+            ),
+            ProblemDetails::TangentDiscontinuity { angle } => {
+                write!(f, "Smooth join is {:.1} degrees out of line when interpolated", angle)
+            }
+            ProblemDetails::SegmentCurvatureFlip {
+                segment,
+                sign_1,
+                sign_2,
+            } => {
+                let direction = |sign: &i8| {
+                    if *sign > 0 {
+                        "anticlockwise"
+                    } else {
+                        "clockwise"
+                    }
+                };
+                write!(
+                    f,
+                    "Segment {} bends {} in one master and {} in the other",
+                    segment,
+                    direction(sign_1),
+                    direction(sign_2)
+                )
+            }
+            ProblemDetails::MissingExtrema {
+                missing_1,
+                missing_2,
+            } => write!(
+                f,
+                "Segments without extremum points differ ({} vs {})",
+                missing_1, missing_2
+            ),
+            ProblemDetails::StartPointTypeMismatch { smooth_1, smooth_2 } => write!(
+                f,
+                "Start point is {} in one master and {} in the other",
+                if *smooth_1 { "smooth" } else { "a corner" },
+                if *smooth_2 { "smooth" } else { "a corner" },
+            ),
+            ProblemDetails::SharedShapeInconsistency {
+                problem_type,
+                other_glyph,
+                other_contour,
+            } => write!(
+                f,
+                "{} here, but not in the same shape in {} (contour {})",
+                problem_type, other_glyph, other_contour
+            ),
+            ProblemDetails::IdenticalMaster => write!(f, "Master is identical to the default"),
+            ProblemDetails::MoreProblems { count } => write!(f, "...and {} more", count),
+            ProblemDetails::UncheckedContour { reason } => {
+                write!(f, "Contour not checked: {}", reason)
+            }
+            ProblemDetails::Custom { check, message } => write!(f, "{}: {}", check, message),
+            ProblemDetails::MetricsDrift {
+                metric,
+                middle_master_name,
+                value_1,
+                middle_value,
+                value_2,
+            } => write!(
+                f,
+                "{} is not monotonic ({} at {} is outside {} to {})",
+                metric, middle_value, middle_master_name, value_1, value_2
+            ),
+        }
+    }
 }
 
 impl Problem {
@@ -56,7 +551,7 @@ impl Problem {
             master_2_name: g2.master_name.to_string(),
             master_1_index: g1.master_index,
             master_2_index: g2.master_index,
-            tolerance: None,
+            badness: None,
             contour: None,
             node: None,
             details: ProblemDetails::PathCount { count_1, count_2 },
@@ -75,7 +570,7 @@ impl Problem {
             master_2_name: g2.master_name.to_string(),
             master_1_index: g1.master_index,
             master_2_index: g2.master_index,
-            tolerance: None,
+            badness: None,
             contour: Some(path_index),
             node: None,
             details: ProblemDetails::NodeCount { count_1, count_2 },
@@ -97,7 +592,7 @@ impl Problem {
             master_2_index: g2.master_index,
             contour: Some(contour),
             node: Some(node),
-            tolerance: None,
+            badness: None,
             details: ProblemDetails::NodeIncompatibility {
                 is_control_1,
                 is_control_2,
@@ -111,42 +606,64 @@ impl Problem {
         tolerance: f64,
         order_1: Vec<usize>,
         order_2: Vec<usize>,
+        reversed: Vec<usize>,
     ) -> Problem {
         Problem {
             master_1_name: g1.master_name.to_string(),
             master_2_name: g2.master_name.to_string(),
             master_1_index: g1.master_index,
             master_2_index: g2.master_index,
-            tolerance: Some(tolerance),
+            badness: Some(Badness::from_ratio(tolerance)),
             contour: None,
             node: None,
-            details: ProblemDetails::ContourOrder { order_1, order_2 },
+            details: ProblemDetails::ContourOrder {
+                order_1,
+                order_2,
+                reversed,
+            },
         }
     }
 
+    /// `details` should be a [ProblemDetails::WrongStartPoint]
     pub(crate) fn wrong_start_point(
         g1: &Glyph,
         g2: &Glyph,
         tolerance: f64,
         contour: usize,
-        proposed_point: usize,
-        reverse: bool,
+        details: ProblemDetails,
     ) -> Problem {
+        debug_assert!(matches!(details, ProblemDetails::WrongStartPoint { .. }));
         Problem {
             master_1_name: g1.master_name.to_string(),
             master_2_name: g2.master_name.to_string(),
             master_1_index: g1.master_index,
             master_2_index: g2.master_index,
-            tolerance: Some(tolerance),
+            badness: Some(Badness::from_ratio(tolerance)),
             contour: Some(contour),
             node: None,
-            details: ProblemDetails::WrongStartPoint {
-                proposed_point,
-                reverse,
-            },
+            details,
         }
     }
 
+    pub(crate) fn contour_direction(
+        g1: &Glyph,
+        g2: &Glyph,
+        tolerance: f64,
+        contour: usize,
+    ) -> Problem {
+        Problem {
+            master_1_name: g1.master_name.to_string(),
+            master_2_name: g2.master_name.to_string(),
+            master_1_index: g1.master_index,
+            master_2_index: g2.master_index,
+            badness: Some(Badness::from_ratio(tolerance)),
+            contour: Some(contour),
+            node: None,
+            details: ProblemDetails::ContourDirection,
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
     pub(crate) fn overweight(
         g1: &Glyph,
         g2: &Glyph,
@@ -154,6 +671,9 @@ impl Problem {
         tolerance: f64,
         value_1: f64,
         value_2: f64,
+        t: f64,
+        upem: u16,
+        midway_ratio: f64,
     ) -> Problem {
         Problem {
             master_1_name: g1.master_name.to_string(),
@@ -161,12 +681,20 @@ impl Problem {
             master_1_index: g1.master_index,
             master_2_index: g2.master_index,
             contour: Some(contour),
-            tolerance: Some(tolerance),
+            badness: Some(Badness::from_ratio(tolerance)),
             node: None,
-            details: ProblemDetails::Overweight { value_1, value_2 },
+            details: ProblemDetails::Overweight {
+                value_1,
+                value_2,
+                permille_1: value_1.sqrt() / upem as f64 * 1000.0,
+                permille_2: value_2.sqrt() / upem as f64 * 1000.0,
+                midway_ratio,
+                t,
+            },
         }
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub(crate) fn underweight(
         g1: &Glyph,
         g2: &Glyph,
@@ -174,6 +702,9 @@ impl Problem {
         tolerance: f64,
         value_1: f64,
         value_2: f64,
+        t: f64,
+        upem: u16,
+        midway_ratio: f64,
     ) -> Problem {
         Problem {
             master_1_name: g1.master_name.to_string(),
@@ -181,8 +712,15 @@ impl Problem {
             master_1_index: g1.master_index,
             master_2_index: g2.master_index,
             contour: Some(contour),
-            tolerance: Some(tolerance),
-            details: ProblemDetails::Underweight { value_1, value_2 },
+            badness: Some(Badness::from_ratio(tolerance)),
+            details: ProblemDetails::Underweight {
+                value_1,
+                value_2,
+                permille_1: value_1.sqrt() / upem as f64 * 1000.0,
+                permille_2: value_2.sqrt() / upem as f64 * 1000.0,
+                midway_ratio,
+                t,
+            },
             node: None,
         }
     }
@@ -201,11 +739,530 @@ impl Problem {
             master_2_index: g2.master_index,
             contour: Some(contour),
             node: Some(node),
-            tolerance: Some(tolerance),
-            details: ProblemDetails::Kink,
+            badness: Some(Badness::from_ratio(tolerance)),
+            details: ProblemDetails::Kink { trajectory: None },
         }
     }
 
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn likely_overlap_removal(
+        g1: &Glyph,
+        g2: &Glyph,
+        contour: usize,
+        count_1: usize,
+        count_2: usize,
+        density_1: f64,
+        density_2: f64,
+    ) -> Problem {
+        Problem {
+            master_1_name: g1.master_name.to_string(),
+            master_2_name: g2.master_name.to_string(),
+            master_1_index: g1.master_index,
+            master_2_index: g2.master_index,
+            contour: Some(contour),
+            node: None,
+            badness: None,
+            details: ProblemDetails::LikelyOverlapRemoval {
+                count_1,
+                count_2,
+                density_1,
+                density_2,
+            },
+        }
+    }
+
+    pub(crate) fn containment_change(
+        g1: &Glyph,
+        g2: &Glyph,
+        contour: usize,
+        container: usize,
+        inside_in_masters: bool,
+    ) -> Problem {
+        Problem {
+            master_1_name: g1.master_name.to_string(),
+            master_2_name: g2.master_name.to_string(),
+            master_1_index: g1.master_index,
+            master_2_index: g2.master_index,
+            contour: Some(contour),
+            node: None,
+            badness: None,
+            details: ProblemDetails::ContainmentChange {
+                container,
+                inside_in_masters,
+            },
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn metrics_drift(
+        g1: &Glyph,
+        g2: &Glyph,
+        metric: Metric,
+        middle: &Glyph,
+        value_1: f64,
+        middle_value: f64,
+        value_2: f64,
+    ) -> Problem {
+        Problem {
+            master_1_name: g1.master_name.to_string(),
+            master_2_name: g2.master_name.to_string(),
+            master_1_index: g1.master_index,
+            master_2_index: g2.master_index,
+            contour: None,
+            node: None,
+            badness: None,
+            details: ProblemDetails::MetricsDrift {
+                metric,
+                middle_master_name: middle.master_name.to_string(),
+                value_1,
+                middle_value,
+                value_2,
+            },
+        }
+    }
+
+    pub(crate) fn centroid_jump(
+        g1: &Glyph,
+        g2: &Glyph,
+        contour: usize,
+        reason: &str,
+        distance: f64,
+    ) -> Problem {
+        Problem {
+            master_1_name: g1.master_name.to_string(),
+            master_2_name: g2.master_name.to_string(),
+            master_1_index: g1.master_index,
+            master_2_index: g2.master_index,
+            contour: Some(contour),
+            node: None,
+            badness: None,
+            details: ProblemDetails::CentroidJump {
+                reason: reason.to_string(),
+                distance,
+            },
+        }
+    }
+
+    pub(crate) fn point_crossing(
+        g1: &Glyph,
+        g2: &Glyph,
+        contour: usize,
+        node: usize,
+        next_node: usize,
+    ) -> Problem {
+        Problem {
+            master_1_name: g1.master_name.to_string(),
+            master_2_name: g2.master_name.to_string(),
+            master_1_index: g1.master_index,
+            master_2_index: g2.master_index,
+            contour: Some(contour),
+            node: Some(node),
+            badness: None,
+            details: ProblemDetails::PointCrossing { next_node },
+        }
+    }
+
+    pub(crate) fn point_collision(
+        g1: &Glyph,
+        g2: &Glyph,
+        contour: usize,
+        node: usize,
+        next_node: usize,
+        t: f64,
+    ) -> Problem {
+        Problem {
+            master_1_name: g1.master_name.to_string(),
+            master_2_name: g2.master_name.to_string(),
+            master_1_index: g1.master_index,
+            master_2_index: g2.master_index,
+            contour: Some(contour),
+            node: Some(node),
+            badness: None,
+            details: ProblemDetails::PointCollision { next_node, t },
+        }
+    }
+
+    pub(crate) fn missing_extrema(
+        g1: &Glyph,
+        g2: &Glyph,
+        contour: usize,
+        missing_1: usize,
+        missing_2: usize,
+    ) -> Problem {
+        Problem {
+            master_1_name: g1.master_name.to_string(),
+            master_2_name: g2.master_name.to_string(),
+            master_1_index: g1.master_index,
+            master_2_index: g2.master_index,
+            contour: Some(contour),
+            node: None,
+            badness: None,
+            details: ProblemDetails::MissingExtrema {
+                missing_1,
+                missing_2,
+            },
+        }
+    }
+
+    pub(crate) fn fill_rule_artifact(
+        g1: &Glyph,
+        g2: &Glyph,
+        filled_area: f64,
+        expected_area: f64,
+    ) -> Problem {
+        Problem {
+            master_1_name: g1.master_name.to_string(),
+            master_2_name: g2.master_name.to_string(),
+            master_1_index: g1.master_index,
+            master_2_index: g2.master_index,
+            contour: None,
+            node: None,
+            badness: None,
+            details: ProblemDetails::FillRuleArtifact {
+                filled_area,
+                expected_area,
+            },
+        }
+    }
+
+    pub(crate) fn handle_length(
+        g1: &Glyph,
+        g2: &Glyph,
+        contour: usize,
+        node: usize,
+        ratio_1: f64,
+        ratio_2: f64,
+    ) -> Problem {
+        Problem {
+            master_1_name: g1.master_name.to_string(),
+            master_2_name: g2.master_name.to_string(),
+            master_1_index: g1.master_index,
+            master_2_index: g2.master_index,
+            contour: Some(contour),
+            node: Some(node),
+            badness: None,
+            details: ProblemDetails::HandleLength { ratio_1, ratio_2 },
+        }
+    }
+
+    pub(crate) fn frechet_distance(
+        g1: &Glyph,
+        g2: &Glyph,
+        contour: usize,
+        distance: f64,
+    ) -> Problem {
+        Problem {
+            master_1_name: g1.master_name.to_string(),
+            master_2_name: g2.master_name.to_string(),
+            master_1_index: g1.master_index,
+            master_2_index: g2.master_index,
+            contour: Some(contour),
+            node: None,
+            badness: None,
+            details: ProblemDetails::FrechetDistance { distance },
+        }
+    }
+
+    pub(crate) fn extrapolation_collapse(
+        g1: &Glyph,
+        g2: &Glyph,
+        contour: usize,
+        t: f64,
+        ratio: f64,
+    ) -> Problem {
+        Problem {
+            master_1_name: g1.master_name.to_string(),
+            master_2_name: g2.master_name.to_string(),
+            master_1_index: g1.master_index,
+            master_2_index: g2.master_index,
+            contour: Some(contour),
+            node: None,
+            badness: None,
+            details: ProblemDetails::ExtrapolationCollapse { t, ratio },
+        }
+    }
+
+    #[cfg(feature = "skrifa")]
+    pub(crate) fn instantiation_divergence(
+        g1: &Glyph,
+        g2: &Glyph,
+        contour: usize,
+        node: usize,
+        distance: f64,
+    ) -> Problem {
+        Problem {
+            master_1_name: g1.master_name.to_string(),
+            master_2_name: g2.master_name.to_string(),
+            master_1_index: g1.master_index,
+            master_2_index: g2.master_index,
+            contour: Some(contour),
+            node: Some(node),
+            badness: None,
+            details: ProblemDetails::InstantiationDivergence { distance },
+        }
+    }
+
+    pub(crate) fn tangent_discontinuity(
+        g1: &Glyph,
+        g2: &Glyph,
+        contour: usize,
+        node: usize,
+        angle: f64,
+    ) -> Problem {
+        Problem {
+            master_1_name: g1.master_name.to_string(),
+            master_2_name: g2.master_name.to_string(),
+            master_1_index: g1.master_index,
+            master_2_index: g2.master_index,
+            contour: Some(contour),
+            node: Some(node),
+            badness: None,
+            details: ProblemDetails::TangentDiscontinuity { angle },
+        }
+    }
+
+    pub(crate) fn segment_curvature_flip(
+        g1: &Glyph,
+        g2: &Glyph,
+        contour: usize,
+        segment: usize,
+        sign_1: i8,
+        sign_2: i8,
+    ) -> Problem {
+        Problem {
+            master_1_name: g1.master_name.to_string(),
+            master_2_name: g2.master_name.to_string(),
+            master_1_index: g1.master_index,
+            master_2_index: g2.master_index,
+            contour: Some(contour),
+            node: None,
+            badness: None,
+            details: ProblemDetails::SegmentCurvatureFlip {
+                segment,
+                sign_1,
+                sign_2,
+            },
+        }
+    }
+
+    pub(crate) fn start_point_type_mismatch(
+        g1: &Glyph,
+        g2: &Glyph,
+        contour: usize,
+        smooth_1: bool,
+        smooth_2: bool,
+    ) -> Problem {
+        Problem {
+            master_1_name: g1.master_name.to_string(),
+            master_2_name: g2.master_name.to_string(),
+            master_1_index: g1.master_index,
+            master_2_index: g2.master_index,
+            contour: Some(contour),
+            node: Some(0),
+            badness: None,
+            details: ProblemDetails::StartPointTypeMismatch { smooth_1, smooth_2 },
+        }
+    }
+
+    pub(crate) fn shared_shape_inconsistency(
+        g1: &Glyph,
+        g2: &Glyph,
+        contour: usize,
+        problem_type: &str,
+        other_glyph: &str,
+        other_contour: usize,
+    ) -> Problem {
+        Problem {
+            master_1_name: g1.master_name.to_string(),
+            master_2_name: g2.master_name.to_string(),
+            master_1_index: g1.master_index,
+            master_2_index: g2.master_index,
+            contour: Some(contour),
+            node: None,
+            badness: None,
+            details: ProblemDetails::SharedShapeInconsistency {
+                problem_type: problem_type.to_string(),
+                other_glyph: other_glyph.to_string(),
+                other_contour,
+            },
+        }
+    }
+
+    pub(crate) fn identical_master(g1: &Glyph, g2: &Glyph) -> Problem {
+        Problem {
+            master_1_name: g1.master_name.to_string(),
+            master_2_name: g2.master_name.to_string(),
+            master_1_index: g1.master_index,
+            master_2_index: g2.master_index,
+            contour: None,
+            node: None,
+            badness: None,
+            details: ProblemDetails::IdenticalMaster,
+        }
+    }
+
+    pub(crate) fn unchecked_contour(
+        g1: &Glyph,
+        g2: &Glyph,
+        contour: usize,
+        reason: &str,
+    ) -> Problem {
+        Problem {
+            master_1_name: g1.master_name.to_string(),
+            master_2_name: g2.master_name.to_string(),
+            master_1_index: g1.master_index,
+            master_2_index: g2.master_index,
+            contour: Some(contour),
+            node: None,
+            badness: None,
+            details: ProblemDetails::UncheckedContour {
+                reason: reason.to_string(),
+            },
+        }
+    }
+
+    /// Create a problem from a custom [crate::Check]
+    ///
+    /// The contour, node and badness are unset; fill them in if relevant.
+    pub fn custom(g1: &Glyph, g2: &Glyph, check: &str, message: &str) -> Problem {
+        Problem {
+            master_1_name: g1.master_name.to_string(),
+            master_2_name: g2.master_name.to_string(),
+            master_1_index: g1.master_index,
+            master_2_index: g2.master_index,
+            contour: None,
+            node: None,
+            badness: None,
+            details: ProblemDetails::Custom {
+                check: check.to_string(),
+                message: message.to_string(),
+            },
+        }
+    }
+
+    /// Where this problem's node (or, failing that, contour) is in a glyph, in font units
+    ///
+    /// For problems with a node, this is the position of that node; for problems
+    /// with only a contour, it is the contour's center of mass. `contour` is the
+    /// contour index to look at in `glyph`, which may differ from the problem's
+    /// if the glyph's contours are in a different order.
+    fn point_in(&self, glyph: &Glyph, contour: usize) -> Option<Point> {
+        match self.node {
+            Some(node) => Some(glyph.points.get(contour)?.get(node)?.point),
+            None => {
+                let vector = glyph.green_vectors.get(contour)?;
+                Some(Point::new(vector[1], vector[2]))
+            }
+        }
+    }
+
+    /// Where this problem is in the first master, in font units
+    ///
+    /// Returns `None` for problems which don't relate to a contour.
+    pub fn point_at(&self, glyph_a: &Glyph) -> Option<Point> {
+        self.point_in(glyph_a, self.contour?)
+    }
+
+    /// Where this problem is at an instance between the two masters, in font units
+    ///
+    /// `t` is the position of the instance between `glyph_a` (at 0.0) and
+    /// `glyph_b` (at 1.0), which should be the glyphs the problem was found in.
+    /// This lets an editor put the cursor on the problem in whichever instance
+    /// the user is looking at. Contours are matched up as in
+    /// [crate::run_tests_with] with the same `options`.
+    pub fn position_at(
+        &self,
+        glyph_a: &Glyph,
+        glyph_b: &Glyph,
+        t: f64,
+        options: &RunTestsOptions,
+    ) -> Option<Point> {
+        let contour = self.contour?;
+        let contour_b = *contour_mapping(glyph_a, glyph_b, options).get(contour)?;
+        let point_a = self.point_in(glyph_a, contour)?;
+        let point_b = self.point_in(glyph_b, contour_b)?;
+        Some(point_a.lerp(point_b, t))
+    }
+
+    /// Order problems for presenting to a person reading a report
+    ///
+    /// Problems with the whole glyph come first, then problems are ordered by
+    /// contour, then by node (with problems affecting the whole contour before
+    /// those at a particular node), and then from most to least severe.
+    /// Problems without a badness come after those with one. Use this with
+    /// `sort_by` to replace the order in which the checks found the problems.
+    pub fn cmp_for_report(&self, other: &Problem) -> Ordering {
+        let badness = |p: &Problem| p.badness.map(|b| b.value());
+        self.contour
+            .cmp(&other.contour)
+            .then(self.node.cmp(&other.node))
+            .then_with(|| match (badness(self), badness(other)) {
+                (Some(a), Some(b)) => b.total_cmp(&a),
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (None, None) => Ordering::Equal,
+            })
+    }
+
+    /// Where a font editor should go to show this problem to the user
+    ///
+    /// The target is always the second master of the pair, which is the one
+    /// the problem describes (for example, the master whose start point
+    /// should move); editors map [NavTarget::master_index] to a location
+    /// using the same list of masters they passed in. The contour and node
+    /// are populated as follows:
+    ///
+    /// * Contour and node: `NodeIncompatibility`, `Kink`, `PointCrossing`,
+    ///   `PointCollision`, `HandleLength`, `InstantiationDivergence`, `TangentDiscontinuity`
+    ///   and `StartPointTypeMismatch`.
+    /// * Contour, with node 0 (the current start point): `WrongStartPoint`
+    ///   and `ContourDirection`.
+    /// * Contour only: `NodeCount`, `Overweight`, `Underweight`,
+    ///   `LikelyOverlapRemoval`, `ContainmentChange`, `CentroidJump`,
+    ///   `MissingExtrema`, `SegmentCurvatureFlip`, `FrechetDistance`,
+    ///   `ExtrapolationCollapse`, `SharedShapeInconsistency` and
+    ///   `UncheckedContour`.
+    /// * Neither (the whole glyph): `PathCount`, `ContourOrder`,
+    ///   `MetricsDrift`, `FillRuleArtifact`, `IdenticalMaster` and
+    ///   `MoreProblems`. `Custom` problems have whatever the check set.
+    ///
+    /// Contours after the structural checks are numbered as in the first
+    /// master; if there is also a `ContourOrder` problem, the contour in the
+    /// second master may be at a different index.
+    pub fn navigation_target(&self) -> NavTarget {
+        let node = match self.details {
+            ProblemDetails::WrongStartPoint { .. } | ProblemDetails::ContourDirection => {
+                self.contour.map(|_| self.node.unwrap_or(0))
+            }
+            _ => self.node,
+        };
+        NavTarget {
+            master_index: self.master_2_index,
+            master_name: self.master_2_name.clone(),
+            contour: self.contour,
+            node,
+        }
+    }
+
+    /// Is this problem only advice, rather than a sign that the glyph
+    /// interpolates badly?
+    ///
+    /// Advisory problems (`MissingExtrema`, `StartPointTypeMismatch`,
+    /// `HandleLength`, `SharedShapeInconsistency`, `UncheckedContour` and
+    /// `IdenticalMaster`) point out things which may be worth tidying up, but
+    /// the glyph still draws correctly throughout the interpolation.
+    pub fn is_advisory(&self) -> bool {
+        matches!(
+            self.details,
+            ProblemDetails::MissingExtrema { .. }
+                | ProblemDetails::StartPointTypeMismatch { .. }
+                | ProblemDetails::HandleLength { .. }
+                | ProblemDetails::SharedShapeInconsistency { .. }
+                | ProblemDetails::UncheckedContour { .. }
+                | ProblemDetails::IdenticalMaster
+        )
+    }
+
     pub fn problem_type(&self) -> String {
         match self.details {
             ProblemDetails::PathCount { .. } => "PathCount".to_string(),
@@ -213,9 +1270,32 @@ impl Problem {
             ProblemDetails::NodeIncompatibility { .. } => "NodeIncompatibility".to_string(),
             ProblemDetails::ContourOrder { .. } => "ContourOrder".to_string(),
             ProblemDetails::WrongStartPoint { .. } => "WrongStartPoint".to_string(),
+            ProblemDetails::ContourDirection => "ContourDirection".to_string(),
             ProblemDetails::Overweight { .. } => "Overweight".to_string(),
             ProblemDetails::Underweight { .. } => "Underweight".to_string(),
-            ProblemDetails::Kink => "Kink".to_string(),
+            ProblemDetails::Kink { .. } => "Kink".to_string(),
+            ProblemDetails::LikelyOverlapRemoval { .. } => "LikelyOverlapRemoval".to_string(),
+            ProblemDetails::ContainmentChange { .. } => "ContainmentChange".to_string(),
+            ProblemDetails::CentroidJump { .. } => "CentroidJump".to_string(),
+            ProblemDetails::PointCrossing { .. } => "PointCrossing".to_string(),
+            ProblemDetails::PointCollision { .. } => "PointCollision".to_string(),
+            ProblemDetails::FillRuleArtifact { .. } => "FillRuleArtifact".to_string(),
+            ProblemDetails::HandleLength { .. } => "HandleLength".to_string(),
+            ProblemDetails::FrechetDistance { .. } => "FrechetDistance".to_string(),
+            ProblemDetails::ExtrapolationCollapse { .. } => "ExtrapolationCollapse".to_string(),
+            ProblemDetails::InstantiationDivergence { .. } => "InstantiationDivergence".to_string(),
+            ProblemDetails::TangentDiscontinuity { .. } => "TangentDiscontinuity".to_string(),
+            ProblemDetails::SegmentCurvatureFlip { .. } => "SegmentCurvatureFlip".to_string(),
+            ProblemDetails::MissingExtrema { .. } => "MissingExtrema".to_string(),
+            ProblemDetails::StartPointTypeMismatch { .. } => "StartPointTypeMismatch".to_string(),
+            ProblemDetails::SharedShapeInconsistency { .. } => {
+                "SharedShapeInconsistency".to_string()
+            }
+            ProblemDetails::UncheckedContour { .. } => "UncheckedContour".to_string(),
+            ProblemDetails::IdenticalMaster => "IdenticalMaster".to_string(),
+            ProblemDetails::MoreProblems { .. } => "MoreProblems".to_string(),
+            ProblemDetails::Custom { ref check, .. } => check.clone(),
+            ProblemDetails::MetricsDrift { .. } => "MetricsDrift".to_string(),
         }
     }
 }
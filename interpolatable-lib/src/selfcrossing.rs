@@ -0,0 +1,52 @@
+use kurbo::{flatten, BezPath, PathEl, Point, Shape};
+
+/// A contour whose net area is less than this fraction of its bounding box may cancel out
+const CANCELLED_AREA_RATIO: f64 = 0.05;
+/// The accuracy (in font units) with which curves are flattened to find crossings
+const FLATTEN_TOLERANCE: f64 = 0.5;
+
+/// Whether segments `a0`-`a1` and `b0`-`b1` cross at a point inside both of them
+fn segments_cross(a0: Point, a1: Point, b0: Point, b1: Point) -> bool {
+    let side = |p: Point, q0: Point, q1: Point| (q1 - q0).cross(p - q0);
+    let (d0, d1) = (side(b0, a0, a1), side(b1, a0, a1));
+    let (d2, d3) = (side(a0, b0, b1), side(a1, b0, b1));
+    d0 * d1 < 0.0 && d2 * d3 < 0.0
+}
+
+/// Whether a contour crosses itself
+fn crosses_itself(contour: &BezPath) -> bool {
+    let mut points = vec![];
+    flatten(contour, FLATTEN_TOLERANCE, |el| match el {
+        PathEl::MoveTo(p) | PathEl::LineTo(p) => points.push(p),
+        _ => {}
+    });
+    let n = points.len();
+    let segment = |i: usize| (points[i], points[(i + 1) % n]);
+    for i in 0..n {
+        // Neighbouring segments share an end point, so can't cross
+        for j in (i + 2)..n {
+            if i == 0 && j == n - 1 {
+                continue;
+            }
+            let ((a0, a1), (b0, b1)) = (segment(i), segment(j));
+            if segments_cross(a0, a1, b0, b1) {
+                return true;
+            }
+        }
+    }
+    false
+}
+
+/// Whether a contour crosses itself so that its areas cancel out
+///
+/// A figure-eight has lobes wound in opposite directions, so its net area is
+/// close to zero however large it is drawn. The statistics computed from the
+/// area are then meaningless, and the weight and start point checks would
+/// report nonsense. Thin contours also have small areas, but don't cross
+/// themselves.
+pub(crate) fn area_cancels_out(contour: &BezPath) -> bool {
+    let bbox_area = contour.bounding_box().area();
+    bbox_area > 0.0
+        && contour.area().abs() < bbox_area * CANCELLED_AREA_RATIO
+        && crosses_itself(contour)
+}
@@ -4,10 +4,60 @@ use kurbo::Affine;
 
 use crate::{
     isomorphism::{Characteristic, Isomorphisms},
+    kink::{is_smooth, kink_candidates, DEFAULT_SMOOTHNESS},
+    problems::Problem,
     utils::VdiffHypo2,
-    Glyph,
+    GlyfPoint, Glyph,
 };
 
+/// How circle-like a contour is, from its feature vector: 1 for a circle
+///
+/// This is high when the contour's spread is the same in every direction (the
+/// standard deviations are equal and there is no correlation between x and y).
+fn circularity(vector: &[f64]) -> f64 {
+    let (stddev_x, stddev_y) = (vector[3].abs(), vector[4].abs());
+    if vector[0] == 0.0 || stddev_x.max(stddev_y) == 0.0 {
+        return 0.0;
+    }
+    let isotropy = stddev_x.min(stddev_y) / stddev_x.max(stddev_y);
+    let correlation = (vector[5] / vector[0].abs()).abs().min(1.0);
+    isotropy * (1.0 - correlation)
+}
+
+/// How much to trust a wrong start point reported for this pair of contours
+///
+/// The start points of near-circular contours are easily misjudged (this is
+/// the case hundreds of times in RobotoSerif-Italic), so the confidence is
+/// one minus the circularity of the more circle-like of the two.
+pub(crate) fn start_point_confidence(m0_vector: &[f64], m1_vector: &[f64]) -> f64 {
+    1.0 - circularity(m0_vector).max(circularity(m1_vector))
+}
+
+/// The smallest leeway used by [default_leeway]
+const MIN_LEEWAY: usize = 3;
+
+/// The leeway used by [test_starting_point] for a contour of `num_points` points
+/// if none is given: a tenth of the contour, but at least three points
+pub(crate) fn default_leeway(num_points: usize) -> usize {
+    (num_points / 10).max(MIN_LEEWAY)
+}
+
+/// Look for a better starting point for contour `ix` of the second master
+///
+/// A proposed starting point within `leeway` points of the current one is
+/// checked again after rotating both contours to align their major axes, and
+/// is only reported if it is still the best match. This is what stops slightly
+/// rotated, near-circular contours from being reported as having the wrong
+/// start point, so a larger leeway reports fewer of these false positives, at
+/// the cost of possibly missing start points which really are slightly off.
+/// Defaults to [default_leeway] of the contour's length.
+///
+/// Returns the tolerance, the proposed starting point, whether the contour
+/// should be reversed, and the number of nodes (the shorter way around the
+/// contour) between the current and proposed starting points. The tolerance
+/// is the match ratio below which the start point is reported as wrong; it
+/// doesn't depend on the tolerance being tested, so that it can be compared
+/// with any tolerance.
 pub(crate) fn test_starting_point(
     glyph_b: &Glyph,
     m0_isomorphisms: &Isomorphisms,
@@ -15,8 +65,8 @@ pub(crate) fn test_starting_point(
     m0_vectors: &[Vec<f64>],
     m1_vectors: &[Vec<f64>],
     ix: usize,
-    tolerance: f64,
-) -> Option<(f64, usize, bool)> {
+    leeway: Option<usize>,
+) -> Option<(f64, usize, bool, usize)> {
     let c0 = m0_isomorphisms.get(0)?;
     let costs: Vec<f64> = m1_isomorphisms
         .iter()
@@ -27,10 +77,15 @@ pub(crate) fn test_starting_point(
         .copied()
         .enumerate()
         .min_by(|(_, a), (_, b)| a.total_cmp(b))?;
-    let mut first_cost = *costs.first()?;
+    let first_cost = *costs.first()?;
+    let mut this_tolerance = if first_cost != 0.0 {
+        min_cost / first_cost
+    } else {
+        1.0
+    };
     let proposed_point = m1_isomorphisms.get(min_index)?.rotation;
     let reverse = m1_isomorphisms.get(min_index)?.reverse;
-    if min_cost < first_cost * tolerance {
+    if min_cost < first_cost {
         // c0 is the first isomorphism of the m0 master
         // m1_isomorphisms is list of all isomorphisms of the m1 master
         //
@@ -39,16 +94,18 @@ pub(crate) fn test_starting_point(
         // example the case hundreds of times in
         // RobotoSerif-Italic[GRAD,opsz,wdth,wght].ttf
         //
-        // If the proposed point is only one off from the first
-        // point (and not reversed), try harder:
+        // If the proposed point is only a few points off from the
+        // first point (and not reversed), try harder:
         //
         // Find the major eigenvector of the covariance matrix,
         // and rotate the contours by that angle. Then find the
         // closest point again.  If it matches this time, let it
         // pass.
         let num_points = glyph_b.points.get(ix)?.len();
-        let leeway = 3usize;
-        if !reverse && (proposed_point <= leeway || proposed_point >= num_points - leeway) {
+        let leeway = leeway.unwrap_or_else(|| default_leeway(num_points));
+        if !reverse
+            && (proposed_point <= leeway || proposed_point >= num_points.saturating_sub(leeway))
+        {
             // Recover the covariance matrix from the GreenVectors.
             let mut transforms = vec![];
             for vector in [m0_vectors.get(ix)?, m1_vectors.get(ix)?].iter() {
@@ -101,18 +158,84 @@ pub(crate) fn test_starting_point(
                 .iter()
                 .map(|c1| new_c0.vdiff_hypot2(&c1.rotated_list))
                 .collect();
-            first_cost = *costs.first()?;
+            let first_cost = *costs.first()?;
             (min_index, min_cost) = costs
                 .iter()
                 .copied()
                 .enumerate()
                 .min_by(|(_, a), (_, b)| a.total_cmp(b))?;
+            // The start point is only wrong if it is wrong both before and
+            // after rotating the contours
+            if first_cost != 0.0 {
+                this_tolerance = this_tolerance.max(min_cost / first_cost);
+            } else {
+                this_tolerance = 1.0;
+            }
         }
     }
-    let this_tolerance = if first_cost != 0.0 {
-        min_cost / first_cost
+    let num_points = glyph_b.points.get(ix)?.len();
+    let rotation = m1_isomorphisms.get(min_index)?.rotation % num_points.max(1);
+    let rotation_delta = rotation.min(num_points - rotation);
+    Some((this_tolerance, min_index, reverse, rotation_delta))
+}
+
+/// Whether moving the start point as proposed would make the contour kink more
+///
+/// `proposal` is the isomorphism of `contour1` chosen by
+/// [test_starting_point]. The contour is rotated (and reversed) to start at
+/// the proposed point, and the sharpest kink it then makes against
+/// `contour0` at the midway interpolation is compared with the sharpest kink
+/// it makes as it is. A suggested start point which trades one problem for
+/// another is flagged rather than withheld, since the start point may still
+/// be wrong.
+pub(crate) fn proposal_creates_kink(
+    contour0: &[GlyfPoint],
+    contour1: &[GlyfPoint],
+    proposal: &Characteristic,
+    smoothness: f64,
+) -> bool {
+    let n = contour1.len();
+    if n == 0 || contour0.len() != n {
+        return false;
+    }
+    let mut proposed = contour1.to_vec();
+    if proposal.reverse {
+        proposed.reverse();
+        proposed.rotate_left((n - 1 - proposal.rotation % n) % n);
     } else {
-        1.0
+        proposed.rotate_left(proposal.rotation % n);
+    }
+    let sharpest = |contour1: &[GlyfPoint]| {
+        kink_candidates(contour0, contour1, smoothness)
+            .iter()
+            .map(|candidate| candidate.sharpness())
+            .fold(0.0, f64::max)
     };
-    Some((this_tolerance, min_index, reverse))
+    sharpest(&proposed) > sharpest(contour1)
+}
+
+/// Test whether a contour starts at a smooth point in one master and a corner in the other
+///
+/// The masters remain compatible, but the start point matters to hinting
+/// and to editing, so this is reported as an advisory
+/// `StartPointTypeMismatch` problem. Points are classified as smooth as in
+/// the kink check, using `smoothness` (default 0.1).
+pub(crate) fn test_start_point_type<'a>(
+    glyph_a: &'a Glyph,
+    glyph_b: &'a Glyph,
+    contour0: &[GlyfPoint],
+    contour1: &[GlyfPoint],
+    ix: usize,
+    smoothness: Option<f64>,
+) -> Option<Problem> {
+    let smoothness = smoothness.unwrap_or(DEFAULT_SMOOTHNESS);
+    let smooth_1 = is_smooth(contour0, 0, smoothness)?;
+    let smooth_2 = is_smooth(contour1, 0, smoothness)?;
+    if smooth_1 != smooth_2 {
+        Some(Problem::start_point_type_mismatch(
+            glyph_a, glyph_b, ix, smooth_1, smooth_2,
+        ))
+    } else {
+        None
+    }
 }
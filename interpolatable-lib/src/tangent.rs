@@ -0,0 +1,86 @@
+use crate::{
+    basiccompat::test_compatibility, contour_mapping, problems::Problem, Glyph, RunTestsOptions,
+};
+
+/// A join whose handles are within this angle (in degrees) of each other is smooth
+const SMOOTH_ANGLE: f64 = 3.0;
+/// A smooth join is reported if its handles are further out of line than this
+/// (in degrees) at the midway interpolation
+const MAX_TANGENT_ERROR: f64 = 1.0;
+
+/// The angle in degrees between two vectors, or `None` if either is zero
+fn angle_between(a: kurbo::Vec2, b: kurbo::Vec2) -> Option<f64> {
+    if a.length() == 0.0 || b.length() == 0.0 {
+        return None;
+    }
+    Some(a.cross(b).atan2(a.dot(b)).abs().to_degrees())
+}
+
+/// Test that smooth curve-to-curve joins stay smooth during interpolation
+///
+/// At each on-curve point between two off-curve handles where the handles
+/// are aligned (within 3 degrees) in both masters, the handles at the midway
+/// interpolation should be aligned too. Joins which lose their alignment
+/// (by more than a degree, and more than in either master) are reported as
+/// `TangentDiscontinuity` problems, with the angle between the handles.
+///
+/// This overlaps with the kink check which [crate::run_tests] performs, but
+/// looks only at the direction of the handles rather than how far the curve
+/// deviates, so it is stricter. Contours are matched as
+/// [crate::run_tests_with] matches them with the same `options`.
+pub fn test_tangent_continuity(
+    glyph_a: &Glyph,
+    glyph_b: &Glyph,
+    options: &RunTestsOptions,
+) -> Vec<Problem> {
+    let mut problems = vec![];
+    if !test_compatibility(glyph_a, glyph_b).is_empty() {
+        return problems;
+    }
+    let mapping = contour_mapping(glyph_a, glyph_b, options);
+    for (ix, &jx) in mapping.iter().enumerate() {
+        let (Some(contour0), Some(contour1)) = (glyph_a.points.get(ix), glyph_b.points.get(jx))
+        else {
+            continue;
+        };
+        if contour0.len() != contour1.len() {
+            continue;
+        }
+        let len = contour0.len();
+        for (i, (pt0, pt1)) in contour0.iter().zip(contour1.iter()).enumerate() {
+            let prev = (i + len - 1) % len;
+            let next = (i + 1) % len;
+            let (pt0_prev, pt0_next) = (&contour0[prev], &contour0[next]);
+            let (pt1_prev, pt1_next) = (&contour1[prev], &contour1[next]);
+            if !pt0.is_control
+                || pt0_prev.is_control
+                || pt0_next.is_control
+                || pt1_prev.is_control
+                || pt1_next.is_control
+            {
+                continue;
+            }
+            let (Some(angle_0), Some(angle_1)) = (
+                angle_between(pt0.point - pt0_prev.point, pt0_next.point - pt0.point),
+                angle_between(pt1.point - pt1_prev.point, pt1_next.point - pt1.point),
+            ) else {
+                continue;
+            };
+            if angle_0 > SMOOTH_ANGLE || angle_1 > SMOOTH_ANGLE {
+                continue;
+            }
+            let midpoint = pt0.point.lerp(pt1.point, 0.5);
+            let mid_prev = pt0_prev.point.lerp(pt1_prev.point, 0.5);
+            let mid_next = pt0_next.point.lerp(pt1_next.point, 0.5);
+            let Some(angle_mid) = angle_between(midpoint - mid_prev, mid_next - midpoint) else {
+                continue;
+            };
+            if angle_mid > MAX_TANGENT_ERROR && angle_mid > angle_0.max(angle_1) {
+                problems.push(Problem::tangent_discontinuity(
+                    glyph_a, glyph_b, ix, i, angle_mid,
+                ));
+            }
+        }
+    }
+    problems
+}
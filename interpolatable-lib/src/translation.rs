@@ -0,0 +1,45 @@
+use kurbo::Vec2;
+
+use crate::GlyfPoint;
+
+/// How far (in font units) any point may stray from the contour's overall
+/// offset for the contour to still count as purely translated
+pub const TRANSLATION_TOLERANCE: f64 = 1.0;
+
+/// Contours whose points move less than this in total (in font units) between
+/// two masters are not checked by default
+///
+/// Problems found in contours which barely change are noise from rounding
+/// and floating point precision. The default is small enough that such a
+/// contour would also count as translated; raise it to skip contours which
+/// change a little more.
+pub const DEFAULT_MIN_CONTOUR_MOVEMENT: f64 = 1.0;
+
+/// The total distance (in font units) the points of a contour move between two masters
+pub(crate) fn contour_movement(contour0: &[GlyfPoint], contour1: &[GlyfPoint]) -> f64 {
+    contour0
+        .iter()
+        .zip(contour1.iter())
+        .map(|(pt0, pt1)| (pt1.point - pt0.point).length())
+        .sum()
+}
+
+/// If `contour1` is `contour0` moved without any other change, return the offset
+///
+/// A contour which is only translated between masters (a dot or accent moving
+/// around, say) interpolates perfectly, so there's no need to check it further.
+/// Every point must have the same offset, give or take [TRANSLATION_TOLERANCE].
+pub fn translation_offset(contour0: &[GlyfPoint], contour1: &[GlyfPoint]) -> Option<Vec2> {
+    if contour0.is_empty() || contour0.len() != contour1.len() {
+        return None;
+    }
+    let offset = contour1[0].point - contour0[0].point;
+    contour0
+        .iter()
+        .zip(contour1.iter())
+        .all(|(pt0, pt1)| {
+            pt0.is_control == pt1.is_control
+                && ((pt1.point - pt0.point) - offset).length() <= TRANSLATION_TOLERANCE
+        })
+        .then_some(offset)
+}
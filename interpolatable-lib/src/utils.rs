@@ -8,27 +8,39 @@ use skrifa::{
         tables::post::DEFAULT_GLYPH_NAMES, types::Version16Dot16, TableProvider,
     },
     setting::VariationSetting,
-    FontRef, GlyphId,
+    FontRef, GlyphId, MetadataProvider,
 };
+#[cfg(feature = "skrifa")]
+use std::collections::HashSet;
 
 pub(crate) fn lerp_curve(c0: &BezPath, c1: &BezPath) -> Option<BezPath> {
+    lerp_curve_at(c0, c1, 0.5)
+}
+
+/// Interpolate between two compatible curves
+///
+/// Returns `None` if the curves are not structurally compatible.
+pub fn lerp_curve_at(c0: &BezPath, c1: &BezPath, t: f64) -> Option<BezPath> {
+    if c0.elements().len() != c1.elements().len() {
+        return None;
+    }
     let mut new = BezPath::new();
     for (e0, e1) in c0.elements().iter().zip(c1.elements()) {
         match (e0, e1) {
             (kurbo::PathEl::MoveTo(p0), kurbo::PathEl::MoveTo(p1)) => {
-                new.push(kurbo::PathEl::MoveTo(p0.lerp(*p1, 0.5)));
+                new.push(kurbo::PathEl::MoveTo(p0.lerp(*p1, t)));
             }
             (kurbo::PathEl::LineTo(p0), kurbo::PathEl::LineTo(p1)) => {
-                new.push(kurbo::PathEl::LineTo(p0.lerp(*p1, 0.5)));
+                new.push(kurbo::PathEl::LineTo(p0.lerp(*p1, t)));
             }
             (kurbo::PathEl::QuadTo(p0, p1), kurbo::PathEl::QuadTo(q0, q1)) => {
-                new.push(kurbo::PathEl::QuadTo(p0.lerp(*q0, 0.5), p1.lerp(*q1, 0.5)));
+                new.push(kurbo::PathEl::QuadTo(p0.lerp(*q0, t), p1.lerp(*q1, t)));
             }
             (kurbo::PathEl::CurveTo(p0, p1, p2), kurbo::PathEl::CurveTo(q0, q1, q2)) => {
                 new.push(kurbo::PathEl::CurveTo(
-                    p0.lerp(*q0, 0.5),
-                    p1.lerp(*q1, 0.5),
-                    p2.lerp(*q2, 0.5),
+                    p0.lerp(*q0, t),
+                    p1.lerp(*q1, t),
+                    p2.lerp(*q2, t),
                 ));
             }
             (kurbo::PathEl::ClosePath, kurbo::PathEl::ClosePath) => {
@@ -65,13 +77,20 @@ impl VdiffHypo2 for Vec<Vec2> {
 pub struct Matching(pub(crate) Vec<Position>);
 
 impl Matching {
+    /// Reorder data belonging to the second glyph's contours so that it lines
+    /// up with the first glyph's contours
     pub fn reorder<T: Clone>(&self, data: &[T]) -> Vec<T> {
         let mut result = vec![];
         for pos in self.iter() {
-            result.push(data[pos.row].clone());
+            result.push(data[pos.column].clone());
         }
         result
     }
+
+    /// For each contour of the first glyph, the index of the matched contour in the second
+    pub fn mapping(&self) -> Vec<usize> {
+        self.iter().map(|pos| pos.column).collect()
+    }
     pub fn len(&self) -> usize {
         self.0.len()
     }
@@ -166,6 +185,21 @@ pub fn glyph_variations(
     Ok(variations)
 }
 
+/// The glyphs which are mapped from a Unicode codepoint in the font's `cmap` table
+#[cfg(feature = "skrifa")]
+pub fn mapped_glyphs(font: &FontRef) -> HashSet<GlyphId> {
+    font.charmap().mappings().map(|(_, gid)| gid).collect()
+}
+
+/// Is this glyph likely to be shipped to users?
+///
+/// `.notdef` and glyphs which aren't mapped from a codepoint are usually
+/// components or development glyphs, which are not worth checking by default.
+#[cfg(feature = "skrifa")]
+pub fn is_exported(mapped: &HashSet<GlyphId>, gid: GlyphId) -> bool {
+    gid != GlyphId::NOTDEF && mapped.contains(&gid)
+}
+
 #[cfg(feature = "skrifa")]
 pub fn glyph_name_for_id(fontref: &FontRef, gid: usize) -> Result<String, ReadError> {
     if let Ok(post) = fontref.post() {
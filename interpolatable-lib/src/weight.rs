@@ -1,22 +1,40 @@
-use greencurves::ComputeGreenStatistics;
+use greencurves::{ComputeControlStatistics, ComputeGreenStatistics};
 use kurbo::BezPath;
 
-use crate::{problems::Problem, stats_to_vectors, Glyph};
+use crate::{
+    contour_mapping, problems::Problem, stats_to_vectors, utils::lerp_curve_at, Glyph,
+    RunTestsOptions, Statistics,
+};
 
+/// Test whether a contour becomes overweight or underweight during interpolation
+///
+/// `mid` is the interpolation of the contour at `t` (usually 0.5). The
+/// expected size at `t` is the geometric interpolation of the two masters'
+/// sizes, so the severity reported at different values of `t` is comparable;
+/// sampling away from the midpoint catches weight problems which are worst
+/// near one end of the axis. `statistics` should be the kind the vectors
+/// were computed from, so that the midway contour is measured the same way.
+#[allow(clippy::too_many_arguments)]
 pub(crate) fn test_over_underweight<'a>(
     glyph_a: &'a Glyph,
     glyph_b: &'a Glyph,
     m0_vector: &[f64],
     m1_vector: &[f64],
     mid: &BezPath,
+    t: f64,
     tolerance: f64,
     ix: usize,
+    upem: u16,
+    statistics: Statistics,
 ) -> Vec<Problem> {
     let mut problems = vec![];
     if (m0_vector[0] < 0.0) == (m1_vector[0] < 0.0) {
         return problems;
     }
-    let mid_stats = stats_to_vectors(&mid.green_statistics());
+    let mid_stats = match statistics {
+        Statistics::Green => stats_to_vectors(&mid.green_statistics()),
+        Statistics::Control => stats_to_vectors(&mid.control_statistics()),
+    };
     let size0 = m0_vector[0] * m0_vector[0];
     let size1 = m1_vector[0] * m1_vector[0];
     let mid_size = mid_stats[0] * mid_stats[0];
@@ -36,11 +54,18 @@ pub(crate) fn test_over_underweight<'a>(
             this_tolerance,
             size0,
             size1,
+            t,
+            upem,
+            if expected == 0.0 {
+                0.0
+            } else {
+                mid_size / expected
+            },
         ));
     }
 
     // Check for underweight
-    let expected = (size0 * size1).sqrt();
+    let expected = size0.powf(1.0 - t) * size1.powf(t);
     if expected * tolerance > mid_size + 1e-5f64 {
         let this_tolerance = if expected == 0.0 {
             0.0
@@ -54,7 +79,34 @@ pub(crate) fn test_over_underweight<'a>(
             this_tolerance,
             size0,
             size1,
+            t,
+            upem,
+            this_tolerance,
         ));
     }
     problems
 }
+
+/// The perceptual weight of a contour at `t` between two glyphs
+///
+/// `t` is 0.0 at `glyph_a` and 1.0 at `glyph_b`; the contour is matched with
+/// the corresponding contour of `glyph_b` as in [crate::run_tests]. Sampling
+/// this across `t` shows how the weight of a contour (see
+/// [Glyph::contour_weight]) evolves along an axis: the weight checks report
+/// a problem when it strays too far from the geometric interpolation of the
+/// two ends. Returns `None` if the contours are not compatible.
+pub fn interpolated_contour_weight(
+    glyph_a: &Glyph,
+    glyph_b: &Glyph,
+    contour: usize,
+    t: f64,
+) -> Option<f64> {
+    let mapping = contour_mapping(glyph_a, glyph_b, &RunTestsOptions::default());
+    let curve = lerp_curve_at(
+        glyph_a.curves.get(contour)?,
+        glyph_b.curves.get(*mapping.get(contour)?)?,
+        t,
+    )?;
+    let size = stats_to_vectors(&curve.green_statistics())[0];
+    Some(size * size)
+}
@@ -1,25 +1,10 @@
-use ::interpolatable::{BezGlyph, Glyph as TwisterGlyph};
+use ::interpolatable::{BezGlyph, Glyph as TwisterGlyph, RunTestsOptions, Statistics};
 use pyo3::{exceptions::PyTypeError, prelude::*};
 use pythonize::pythonize;
 
 #[pyclass]
 pub struct Glyph(pub TwisterGlyph);
 
-fn decompose_quadratic_segment(points: Vec<(f32, f32)>) -> Vec<((f32, f32), (f32, f32))> {
-    let mut quad_segments = Vec::new();
-    for i in 0..points.len() - 1 {
-        let (x, y) = points[i];
-        let (nx, ny) = points[i + 1];
-        let implied_pt = (0.5 * (x + nx), 0.5 * (y + ny));
-        quad_segments.push((points[i], implied_pt));
-    }
-    quad_segments
-}
-
-fn quad_to_one(bezglyph: &mut BezGlyph, p1: (f32, f32), p2: (f32, f32)) {
-    bezglyph.current().quad_to(p1, p2);
-}
-
 fn replay_recording(bezglyph: &mut BezGlyph, value: Vec<(String, Vec<(f32, f32)>)>) {
     for (command, points) in value {
         match command.as_str() {
@@ -31,9 +16,7 @@ fn replay_recording(bezglyph: &mut BezGlyph, value: Vec<(String, Vec<(f32, f32)>
             }
             "qCurveTo" => {
                 // in theory handle the zero case heres
-                for (pt1, pt2) in decompose_quadratic_segment(points) {
-                    quad_to_one(bezglyph, pt1, pt2);
-                }
+                bezglyph.qcurve_to(&points);
             }
             "curveTo" => {
                 // in theory handle the polycubic case here
@@ -77,7 +60,7 @@ impl Glyph {
 }
 
 #[pyfunction]
-#[pyo3(signature = (glyph_a, glyph_b, tolerance=None, kinkiness=None, upem=None))]
+#[pyo3(signature = (glyph_a, glyph_b, tolerance=None, kinkiness=None, upem=None, max_contour_distance=None, min_contour_movement=None, smoothness=None, ignore_contour_order=false, weight_samples=None, start_point_leeway=None, control_statistics=false))]
 fn test_interpolatability<'py>(
     py: Python<'py>,
     glyph_a: &Glyph,
@@ -85,8 +68,32 @@ fn test_interpolatability<'py>(
     tolerance: Option<f64>,
     kinkiness: Option<f64>,
     upem: Option<u16>,
+    max_contour_distance: Option<f64>,
+    min_contour_movement: Option<f64>,
+    smoothness: Option<f64>,
+    ignore_contour_order: bool,
+    weight_samples: Option<Vec<f64>>,
+    start_point_leeway: Option<usize>,
+    control_statistics: bool,
 ) -> PyResult<Bound<'py, PyAny>> {
-    let result = ::interpolatable::run_tests(&glyph_a.0, &glyph_b.0, tolerance, kinkiness, upem);
+    let options = RunTestsOptions {
+        tolerance,
+        kinkiness,
+        smoothness,
+        upem,
+        max_contour_distance,
+        min_contour_movement,
+        weight_samples,
+        start_point_leeway,
+        ignore_contour_order,
+        statistics: if control_statistics {
+            Statistics::Control
+        } else {
+            Statistics::Green
+        },
+        ..Default::default()
+    };
+    let result = ::interpolatable::run_tests_with(&glyph_a.0, &glyph_b.0, &options);
     println!("{:?}", result);
     pythonize(py, &result).map_err(|e| PyErr::new::<PyTypeError, _>("Error message"))
 }
@@ -1,12 +1,13 @@
-use std::collections::HashMap;
+use std::collections::HashSet;
 
 use indexmap::IndexMap;
 use interpolatable::{
-    run_tests,
-    utils::{glyph_name_for_id, glyph_variations, DenormalizeLocation},
+    run_tests, test_compatibility,
+    utils::{glyph_name_for_id, glyph_variations, is_exported, mapped_glyphs, DenormalizeLocation},
+    Problem,
 };
 use read_fonts::TableProvider;
-use serde_json::{json, Value};
+use serde::{Deserialize, Serialize};
 use skrifa::{setting::VariationSetting, GlyphId};
 use wasm_bindgen::prelude::*;
 extern crate console_error_panic_hook;
@@ -35,94 +36,256 @@ extern "C" {
     fn log(s: &str);
 }
 
+/// The problems found between two masters of a glyph, with what the
+/// frontend needs to draw them
+///
+/// This is what [FontChecker::report] serializes for each pair of masters;
+/// it can be deserialized again (for example with `serde_wasm_bindgen` from
+/// the value returned by [FontChecker::report_object]) to get typed problems.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MasterPairReport {
+    /// The first master's contours, as SVG path data
+    pub default_outline: Vec<String>,
+    /// The second master's contours, as SVG path data
+    pub outline: Vec<String>,
+    pub midway_location: String,
+    /// The contours at the location midway between the masters, as SVG path data
+    pub midway_outline: Vec<String>,
+    pub problems: Vec<Problem>,
+    pub default_name: String,
+    pub master_name: String,
+    pub master_index: usize,
+    /// The bounding box of all three outlines (`[x_min, y_min, x_max,
+    /// y_max]`), for framing them in the frontend
+    pub bbox: Option<[f64; 4]>,
+    pub upem: u16,
+}
+
 #[wasm_bindgen]
 pub fn check_font(font_data: &[u8]) -> Result<String, JsValue> {
+    let mut checker = FontChecker::new(font_data)?;
+    while checker.check_next_batch(u16::MAX)? {}
+    checker.report()
+}
+
+/// Run only the structural compatibility checks over every glyph in the font
+///
+/// Returns a JSON object mapping each glyph name to `true` if all its masters
+/// are compatible, or `false` otherwise. Glyphs without variations, `.notdef`
+/// and glyphs without a Unicode mapping are omitted.
+/// This is much quicker than `check_font`, so is useful as a first pass.
+#[wasm_bindgen]
+pub fn check_font_fast(font_data: &[u8]) -> Result<String, JsValue> {
     let font = skrifa::FontRef::new(font_data).map_err(|e| e.to_string())?;
+    let num_glyphs = font.maxp().map_err(|e| e.to_string())?.num_glyphs();
+    let mapped = mapped_glyphs(&font);
+    let mut report: IndexMap<String, bool> = IndexMap::new();
+    for gid in 0..num_glyphs {
+        let gid: GlyphId = gid.into();
+        if !is_exported(&mapped, gid) {
+            continue;
+        }
+        let Ok(variations) = glyph_variations(&font, gid) else {
+            continue;
+        };
+        if variations.is_empty() {
+            continue;
+        }
+        let glyphs = std::iter::once(&[][..])
+            .chain(variations.iter().map(|loc| loc.as_slice()))
+            .map(|loc| interpolatable::Glyph::new_from_font(&font, gid, loc))
+            .collect::<Option<Vec<_>>>()
+            .ok_or("Can't convert glyph")?;
+        let compatible = glyphs
+            .windows(2)
+            .all(|pair| test_compatibility(&pair[0], &pair[1]).is_empty());
+        let glyphname = glyph_name_for_id(&font, gid.to_u32() as usize)
+            .unwrap_or_else(|_| format!("gid{}", gid.to_u32()));
+        report.insert(glyphname, compatible);
+    }
+    serde_json::to_string(&report).map_err(|e| e.to_string().into())
+}
+
+/// Checks a font a few glyphs at a time
+///
+/// Checking a large font in one go can take a long time; this lets the
+/// JavaScript side call `check_next_batch` repeatedly, reporting progress
+/// and keeping the page responsive in between.
+#[wasm_bindgen]
+pub struct FontChecker {
+    font_data: Vec<u8>,
+    next_gid: u16,
+    num_glyphs: u16,
+    default_location: Vec<VariationSetting>,
+    locations: Vec<Vec<VariationSetting>>,
+    report: IndexMap<String, Vec<MasterPairReport>>,
+    mapped: HashSet<GlyphId>,
+    include_all: bool,
+}
+
+#[wasm_bindgen]
+impl FontChecker {
+    #[wasm_bindgen(constructor)]
+    pub fn new(font_data: &[u8]) -> Result<FontChecker, JsValue> {
+        let font = skrifa::FontRef::new(font_data).map_err(|e| e.to_string())?;
+        let num_glyphs = font.maxp().map_err(|e| e.to_string())?.num_glyphs();
+        let axis_count = font
+            .fvar()
+            .and_then(|fvar| fvar.axes())
+            .map_err(|e| e.to_string())?
+            .len();
+        let default_location = font
+            .denormalize_location(&vec![0.0; axis_count])
+            .map_err(|e| e.to_string())?;
+        log(&format!("{:?}", default_location));
+        let mapped = mapped_glyphs(&font);
+        Ok(FontChecker {
+            font_data: font_data.to_vec(),
+            next_gid: 0,
+            num_glyphs,
+            default_location,
+            locations: vec![vec![]],
+            report: IndexMap::new(),
+            mapped,
+            include_all: false,
+        })
+    }
+
+    /// Also check `.notdef` and glyphs without a Unicode mapping, which are
+    /// skipped by default
+    pub fn set_include_all(&mut self, include_all: bool) {
+        self.include_all = include_all;
+    }
 
-    let mut report: IndexMap<String, Vec<Value>> = IndexMap::new();
-    let mut glyphname_to_id: HashMap<String, GlyphId> = HashMap::new();
-    let mut locations: Vec<Vec<VariationSetting>> = vec![vec![]];
-    let default_location = font
-        .denormalize_location(&vec![0.0; font.fvar().unwrap().axes().unwrap().len()])
-        .unwrap();
-    log(&format!("{:?}", default_location));
-    for gid in 0..font.maxp().expect("Can't open maxp table").num_glyphs() {
-        let mut default_glyph = interpolatable::Glyph::new_from_font(&font, gid.into(), &[])
-            .expect("Can't convert glyph");
-        default_glyph.master_name = "default".to_string();
-        default_glyph.master_index = 0;
-        if let Ok(variations) = glyph_variations(&font, gid.into()) {
-            let variation_glyphs = variations.iter().map(|loc| {
-                let mut glyph = interpolatable::Glyph::new_from_font(&font, gid.into(), loc)
-                    .expect("Couldn't convert glyph");
-                glyph.master_name = loc
-                    .iter()
-                    .map(|v| format!("{}={}", v.selector, v.value))
-                    .collect::<Vec<_>>()
-                    .join(",");
-                if !locations.contains(loc) {
+    pub fn num_glyphs(&self) -> u16 {
+        self.num_glyphs
+    }
+
+    /// The number of glyphs checked so far
+    pub fn checked(&self) -> u16 {
+        self.next_gid
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.next_gid >= self.num_glyphs
+    }
+
+    /// Check up to `n` more glyphs, returning true if there are more to check
+    pub fn check_next_batch(&mut self, n: u16) -> Result<bool, JsValue> {
+        let font = skrifa::FontRef::new(&self.font_data).map_err(|e| e.to_string())?;
+        let end = self.next_gid.saturating_add(n).min(self.num_glyphs);
+        for gid in self.next_gid..end {
+            if !self.include_all && !is_exported(&self.mapped, gid.into()) {
+                continue;
+            }
+            check_glyph(
+                &font,
+                gid.into(),
+                &self.default_location,
+                &mut self.locations,
+                &mut self.report,
+            )?;
+        }
+        self.next_gid = end;
+        Ok(!self.is_done())
+    }
+
+    /// The report so far, as a JSON string
+    pub fn report(&self) -> Result<String, JsValue> {
+        serde_json::to_string(&self.report).map_err(|e| e.to_string().into())
+    }
+
+    /// The report so far, as a JavaScript object
+    ///
+    /// This is the same as parsing [FontChecker::report], but saves a
+    /// round trip through a string on the JavaScript side.
+    pub fn report_object(&self) -> Result<JsValue, JsValue> {
+        js_sys::JSON::parse(&self.report()?)
+    }
+}
+
+fn check_glyph(
+    font: &skrifa::FontRef,
+    gid: GlyphId,
+    default_location: &[VariationSetting],
+    locations: &mut Vec<Vec<VariationSetting>>,
+    report: &mut IndexMap<String, Vec<MasterPairReport>>,
+) -> Result<(), JsValue> {
+    let mut default_glyph =
+        interpolatable::Glyph::new_from_font(font, gid, &[]).ok_or("Can't convert glyph")?;
+    default_glyph.master_name = "default".to_string();
+    default_glyph.master_index = 0;
+    if let Ok(variations) = glyph_variations(font, gid) {
+        let mut to_test = vec![(default_location, default_glyph)];
+        for loc in variations.iter() {
+            let mut glyph = interpolatable::Glyph::new_from_font(font, gid, loc)
+                .ok_or("Can't convert glyph")?;
+            glyph.master_name = loc
+                .iter()
+                .map(|v| format!("{}={}", v.selector, v.value))
+                .collect::<Vec<_>>()
+                .join(",");
+            glyph.master_index = match locations.iter().position(|x| x == loc) {
+                Some(index) => index,
+                None => {
                     locations.push(loc.clone());
+                    locations.len() - 1
                 }
-                glyph.master_index = locations.iter().position(|x| x == loc).unwrap();
-                (loc, glyph)
-            });
-            let to_test = std::iter::once((&default_location, default_glyph))
-                .chain(variation_glyphs)
-                .collect::<Vec<_>>();
-            for pair in to_test.windows(2) {
-                if let [(before_loc, before), (after_loc, after)] = pair {
-                    // println!("Testing {} vs {}", after.master_name, before.master_name);
-                    let problems = run_tests(
-                        before,
-                        after,
-                        None,
-                        None,
-                        Some(font.head().unwrap().units_per_em()),
-                    );
-                    if !problems.is_empty() {
-                        let glyphname = glyph_name_for_id(&font, gid.into())
-                            .unwrap_or_else(|_| format!("gid{}", gid));
-                        glyphname_to_id.insert(glyphname.clone(), gid.into());
-                        let default_outline: Vec<String> =
-                            before.curves.iter().map(|c| c.to_svg()).collect();
-                        let outline: Vec<String> =
-                            after.curves.iter().map(|c| c.to_svg()).collect();
-                        let serialized_problems = problems
-                            .iter()
-                            .map(|p| serde_json::to_value(p).unwrap())
-                            .collect::<Vec<_>>();
-                        let midway_location = lerp_location(before_loc, after_loc, 0.5);
-                        let midway_glyph = interpolatable::Glyph::new_from_font(
-                            &font,
-                            gid.into(),
-                            &midway_location,
-                        )
-                        .ok_or("Can't convert glyph")?;
-                        let midway_name = midway_location
-                            .iter()
-                            .map(|v| format!("{}={}", v.selector, v.value))
-                            .collect::<Vec<_>>()
-                            .join(",");
-                        let midway_outline = midway_glyph
-                            .curves
-                            .iter()
-                            .map(|v| v.to_svg())
-                            .collect::<Vec<_>>();
-                        report.entry(glyphname.clone()).or_default().push(json!({
-                            "default_outline": default_outline,
-                            "outline": outline,
-                            "midway_location": midway_name,
-                            "midway_outline": midway_outline,
-                            "problems": serialized_problems,
-                            "default_name": before.master_name,
-                            "master_name": after.master_name,
-                            "master_index": after.master_index,
-                        }));
-                    }
+            };
+            to_test.push((loc.as_slice(), glyph));
+        }
+        for pair in to_test.windows(2) {
+            if let [(before_loc, before), (after_loc, after)] = pair {
+                // println!("Testing {} vs {}", after.master_name, before.master_name);
+                let upem = font
+                    .head()
+                    .map(|head| head.units_per_em())
+                    .ok()
+                    .filter(|&upem| upem > 0);
+                let problems = run_tests(before, after, None, None, upem);
+                if !problems.is_empty() {
+                    let glyphname = glyph_name_for_id(font, gid.to_u32() as usize)
+                        .unwrap_or_else(|_| format!("gid{}", gid.to_u32()));
+                    let default_outline: Vec<String> =
+                        before.curves.iter().map(|c| c.to_svg()).collect();
+                    let outline: Vec<String> = after.curves.iter().map(|c| c.to_svg()).collect();
+                    let midway_location = lerp_location(before_loc, after_loc, 0.5);
+                    let midway_glyph =
+                        interpolatable::Glyph::new_from_font(font, gid, &midway_location)
+                            .ok_or("Can't convert glyph")?;
+                    let midway_name = midway_location
+                        .iter()
+                        .map(|v| format!("{}={}", v.selector, v.value))
+                        .collect::<Vec<_>>()
+                        .join(",");
+                    let midway_outline = midway_glyph
+                        .curves
+                        .iter()
+                        .map(|v| v.to_svg())
+                        .collect::<Vec<_>>();
+                    // The bounding box of all three outlines, for framing them in the frontend
+                    let bbox = [before.bounds(), after.bounds(), midway_glyph.bounds()]
+                        .into_iter()
+                        .flatten()
+                        .reduce(|a, b| a.union(b));
+                    report
+                        .entry(glyphname.clone())
+                        .or_default()
+                        .push(MasterPairReport {
+                            default_outline,
+                            outline,
+                            midway_location: midway_name,
+                            midway_outline,
+                            problems,
+                            default_name: before.master_name.clone(),
+                            master_name: after.master_name.clone(),
+                            master_index: after.master_index,
+                            bbox: bbox.map(|r| [r.x0, r.y0, r.x1, r.y1]),
+                            upem: upem.unwrap_or(1000),
+                        });
                 }
             }
         }
     }
-
-    serde_json::to_string(&report).map_err(|e| e.to_string().into())
+    Ok(())
 }
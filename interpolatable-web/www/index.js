@@ -27,14 +27,22 @@ function problem2string(problem) {
     return `Incompatible nodes: Node ${problem.node} <span class="contour-${problem.contour}">contour ${problem.contour}</span> is ${p.is_control_1 ? 'off-curve' : 'on-curve'} in ${problem.master_1_name} vs ${p.is_control_2 ? 'off-curve' : 'on-curve'} in ${problem.master_2_name}`;
   }
   if (problem.type == "ContourOrder") {
-    return `Contour order mismatch: <span class="contour-${problem.order_1}">${problem.order_1}</span> in ${problem.master_1_name} matches with <span class="contour-${problem.order_2}">${problem.order_2}</span> in ${problem.master_2_name}`;
+    let reversed = "";
+    if (problem.reversed && problem.reversed.length) {
+      reversed = ` (and contours ${problem.reversed} are also reversed)`;
+    }
+    return `Contour order mismatch: <span class="contour-${problem.order_1}">${problem.order_1}</span> in ${problem.master_1_name} matches with <span class="contour-${problem.order_2}">${problem.order_2}</span> in ${problem.master_2_name}${reversed}`;
   }
   if (problem.type == "WrongStartPoint") {
     let reverse = "";
     if (problem.reverse) {
       reverse = " (and the contour should be reversed)";
     }
-    return `Wrong start point: <span class="contour-${problem.contour}">contour ${problem.contour}</span> in ${problem.master_2_name} should start at node ${problem.proposed_point} ${reverse}`;
+    let kink = "";
+    if (problem.creates_kink) {
+      kink = ", but this would create a kink";
+    }
+    return `Wrong start point: <span class="contour-${problem.contour}">contour ${problem.contour}</span> in ${problem.master_2_name} should start at node ${problem.proposed_point}, ${problem.rotation_delta} nodes away${reverse}${kink}`;
   }
   if (problem.type == "Overweight") {
     return `Overweight: <span class="contour-${problem.contour}">contour ${problem.contour}</span> becomes overweight`;
@@ -106,6 +114,8 @@ class Interpolatable {
     if ("ready" in message) {
       $("#bigLoadingModal").hide();
       $("#startModal").show();
+    } else if ("progress" in message) {
+      console.log(`Checked ${Math.round(message.progress * 100)}% of glyphs`);
     } else if ("results" in message) {
       $("#spinnerModal").hide();
       this.renderResults(message.results);
@@ -140,7 +150,7 @@ class Interpolatable {
     $("#glyphlist button:first-child").tab("show").addClass("active").trigger("click");
   }
 
-  renderSvg(container, outlines) {
+  renderSvg(container, outlines, bbox, upem) {
     let svg = SVG.adopt(container[0]);
     svg.clear();
     let group = svg.group();
@@ -150,9 +160,12 @@ class Interpolatable {
       outlinegroup.path(contour).fill("none").stroke({ width: 5, color: COLORS[index % COLORS.length] });
     }
     shadowgroup.path(outlines.join(" ")).fill("#000").opacity(0.25).attr({'fill-rule': 'even-odd'});
-    let bbox = group.bbox();
-    svg.viewbox(bbox);
-    group.transform({ flip: "y" });
+    // Font coordinates are y-up, so flip the glyph and frame it with the
+    // bounding box computed in Rust
+    group.attr({ transform: "scale(1 -1)" });
+    let [xMin, yMin, xMax, yMax] = bbox || [0, 0, upem, upem];
+    let pad = upem * 0.05;
+    svg.viewbox(xMin - pad, -yMax - pad, xMax - xMin + 2 * pad, yMax - yMin + 2 * pad);
 }
 
   renderProblemSet(el) {
@@ -178,9 +191,10 @@ class Interpolatable {
           </div>
           </div>
         `);
-      this.renderSvg(problem_html.find(".beforesvg"), problems["default_outline"]);
-      this.renderSvg(problem_html.find(".midwaysvg"), problems["midway_outline"]);
-      this.renderSvg(problem_html.find(".aftersvg"), problems["outline"]);
+      let { bbox, upem } = problems;
+      this.renderSvg(problem_html.find(".beforesvg"), problems["default_outline"], bbox, upem);
+      this.renderSvg(problem_html.find(".midwaysvg"), problems["midway_outline"], bbox, upem);
+      this.renderSvg(problem_html.find(".aftersvg"), problems["outline"], bbox, upem);
       result.append(problem_html);
       let list = $("<ul></ul>");
       for (var problem of problems["problems"]) {
@@ -1,5 +1,7 @@
 var module = import("../pkg/interpolatable_web.js");
 
+const BATCH_SIZE = 50;
+
 async function init() {
   console.log("Loading the module");
   let wasm = await module;
@@ -9,7 +11,14 @@ async function init() {
     // make sure loading is done
     const { font } = event.data;
     try {
-      const results = JSON.parse(wasm.check_font(font));
+      const checker = new wasm.FontChecker(font);
+      while (checker.check_next_batch(BATCH_SIZE)) {
+        self.postMessage({
+          progress: checker.checked() / checker.num_glyphs(),
+        });
+      }
+      const results = checker.report_object();
+      checker.free();
       self.postMessage({ results: results });
     } catch (error) {
       self.postMessage({ error: error.message });